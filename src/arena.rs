@@ -0,0 +1,219 @@
+use crate::pod::{pod_mut, pod_ref};
+use crate::{Mbuf, MbufError, MbufLength, MbufMut, MbufOffset, MbufWriter, Pod, Region};
+
+/// Stored at the beginning of a region managed by an [`MbufArena`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct ArenaHeader {
+    magic: [u8; 8],
+    version: u32,
+    reserved: u32,
+    cursor: u64,
+}
+
+unsafe impl Pod for ArenaHeader {}
+
+impl ArenaHeader {
+    pub const MAGIC: [u8; 8] = *b"PS-ARENA";
+    pub const VERSION: u32 = 1;
+
+    /// Offset at which the next allocation is searched for.
+    pub fn cursor(&self) -> u64 {
+        self.cursor
+    }
+}
+
+/// Bump allocator placing Mbufs with lengths stored as `L` into a [`Region`].
+/// <br>The allocation cursor is persisted in an [`ArenaHeader`] at the beginning of the region,
+/// so the arena can be reopened with [`MbufArena::open`].
+/// <br>Offsets are aligned relative to the start of the region, so its first byte
+/// must be aligned to the largest [`Mbuf::required_align`] of the Mbufs allocated,
+/// e.g. by using an [`crate::AlignedBuffer`].
+pub struct MbufArena<R, L = usize> {
+    writer: MbufWriter<R, L>,
+}
+
+impl<R: Region> MbufArena<R> {
+    /// Creates an empty arena at the beginning of `region`, overwriting its contents.
+    pub fn new(region: R) -> Result<Self, MbufError> {
+        let mut arena = Self {
+            writer: MbufWriter::with_position(region, 0),
+        };
+
+        arena.writer.reserve_bytes(
+            std::mem::size_of::<ArenaHeader>(),
+            std::mem::align_of::<ArenaHeader>(),
+        )?;
+
+        *pod_mut(arena.writer.bytes_mut(), 0)? = ArenaHeader {
+            magic: ArenaHeader::MAGIC,
+            version: ArenaHeader::VERSION,
+            reserved: 0,
+            cursor: 0,
+        };
+
+        arena.store_cursor()?;
+
+        Ok(arena)
+    }
+
+    /// Reopens the arena stored in `region`, restoring its cursor.
+    pub fn open(region: R) -> Result<Self, MbufError> {
+        let header: &ArenaHeader = pod_ref(region.bytes(), 0)?;
+
+        if header.magic != ArenaHeader::MAGIC {
+            return Err(MbufError::InvalidMetadata("bad magic bytes"));
+        }
+
+        if header.version != ArenaHeader::VERSION {
+            return Err(MbufError::InvalidMetadata("unsupported format version"));
+        }
+
+        let cursor = usize::try_from(header.cursor).unwrap_or(usize::MAX);
+
+        if cursor < std::mem::size_of::<ArenaHeader>() || cursor > region.bytes().len() {
+            return Err(MbufError::InvalidMetadata("cursor out of range"));
+        }
+
+        Ok(Self {
+            writer: MbufWriter::with_position(region, cursor),
+        })
+    }
+}
+
+impl<R: Region, L: MbufLength> MbufArena<R, L> {
+    /// Converts this arena into one which stores lengths as `T`.
+    pub fn with_length_type<T: MbufLength>(self) -> MbufArena<R, T> {
+        MbufArena {
+            writer: self.writer.with_length_type(),
+        }
+    }
+
+    /// Number of bytes in use, including the header and padding.
+    pub fn cursor(&self) -> usize {
+        self.writer.position()
+    }
+
+    pub fn region(&self) -> &R {
+        self.writer.region()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.writer.into_inner()
+    }
+
+    /// Allocates an Mbuf holding `metadata` and `length` zeroed elements.
+    pub fn alloc<M: Pod, D: Pod>(
+        &mut self,
+        metadata: M,
+        length: usize,
+    ) -> Result<MbufOffset<M, D, L>, MbufError> {
+        let offset = self.writer.reserve::<M, D>(length)?;
+
+        unsafe {
+            Mbuf::<M, D, L>::init_at_ptr(
+                self.writer.bytes_mut().as_mut_ptr().add(offset),
+                metadata,
+                length,
+            );
+        }
+
+        self.store_cursor()?;
+
+        Ok(MbufOffset::new(offset))
+    }
+
+    /// Allocates an Mbuf holding `metadata` and a copy of `data`.
+    pub fn alloc_from_slice<M: Pod, D: Pod>(
+        &mut self,
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufOffset<M, D, L>, MbufError> {
+        let offset = self.writer.append(metadata, data)?;
+
+        self.store_cursor()?;
+
+        Ok(MbufOffset::new(offset))
+    }
+
+    pub fn get<M: Pod, D: Pod>(
+        &self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<&Mbuf<'_, M, D, L>, MbufError> {
+        offset.resolve(self.writer.region().bytes())
+    }
+
+    pub fn get_mut<M: Pod, D: Pod>(
+        &mut self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<MbufMut<'_, M, D, L>, MbufError> {
+        offset.resolve_mut(self.writer.bytes_mut())
+    }
+
+    fn store_cursor(&mut self) -> Result<(), MbufError> {
+        let cursor = self.writer.position() as u64;
+
+        pod_mut::<ArenaHeader>(self.writer.bytes_mut(), 0)?.cursor = cursor;
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    #[test]
+    fn alloc_and_reopen() {
+        let mut arena = MbufArena::new(AlignedBuffer::new()).unwrap();
+        let zeroed = arena.alloc::<u32, u16>(1, 3).unwrap();
+        let copied = arena.alloc_from_slice(2u64, &[7u8, 8]).unwrap();
+
+        assert_eq!(&**arena.get(zeroed).unwrap(), &[0, 0, 0]);
+        arena.get_mut(zeroed).unwrap()[1] = 5;
+
+        let cursor = arena.cursor();
+        let arena = MbufArena::open(arena.into_inner()).unwrap();
+        assert_eq!(arena.cursor(), cursor);
+        assert_eq!(
+            pod_ref::<ArenaHeader>(arena.region(), 0).unwrap().cursor(),
+            cursor as u64
+        );
+
+        assert_eq!(*arena.get(zeroed).unwrap().get_metadata(), 1);
+        assert_eq!(&**arena.get(zeroed).unwrap(), &[0, 5, 0]);
+        assert_eq!(&**arena.get(copied).unwrap(), &[7, 8]);
+
+        let mut arena = arena.with_length_type::<u32>();
+        let next = arena.alloc_from_slice(3u32, &[9u32]).unwrap();
+        assert!(next.get() >= cursor as u64);
+    }
+
+    #[test]
+    fn open_rejects_bad_header() {
+        let buffer = MbufArena::new(AlignedBuffer::new()).unwrap().into_inner();
+        let len = buffer.len() as u64;
+
+        for (cursor, reason) in [
+            (len, None),
+            (len + 1, Some("cursor out of range")),
+            (0, Some("cursor out of range")),
+        ] {
+            let mut buffer = buffer.clone();
+            pod_mut::<ArenaHeader>(&mut buffer, 0).unwrap().cursor = cursor;
+
+            assert_eq!(
+                MbufArena::open(buffer).err(),
+                reason.map(MbufError::InvalidMetadata)
+            );
+        }
+
+        let mut buffer = buffer.clone();
+        buffer[0] ^= 1;
+        assert_eq!(
+            MbufArena::open(buffer).err(),
+            Some(MbufError::InvalidMetadata("bad magic bytes"))
+        );
+    }
+}
@@ -0,0 +1,182 @@
+use std::alloc::Layout;
+use std::marker::PhantomData;
+use std::ptr::NonNull;
+
+use crate::{Mbuf, MbufLength};
+
+/// A heap-allocated Mbuf<M, D, L> which owns its metadata and elements.
+/// <br>Dereferences to the Mbuf; its elements and metadata are modified through
+/// [`MbufBox::as_mut_slice`] and [`MbufBox::set_metadata`].
+pub struct MbufBox<M, D: 'static, L: MbufLength = usize> {
+    pointer: NonNull<u8>,
+    layout: Layout,
+    _marker: PhantomData<(M, D, L)>,
+}
+
+unsafe impl<M: Send, D: Send, L: MbufLength> Send for MbufBox<M, D, L> {}
+unsafe impl<M: Sync, D: Sync, L: MbufLength> Sync for MbufBox<M, D, L> {}
+
+impl<M, D: 'static, L: MbufLength> MbufBox<M, D, L> {
+    /// Allocates an Mbuf with room for `capacity` elements and a length of zero.
+    /// # Panics
+    /// Panics if `capacity` exceeds the range of `L`, or the Mbuf does not fit into the address space.
+    fn allocate(metadata: M, capacity: usize) -> Self {
+        L::from_usize(capacity).expect("Mbuf length exceeds the range of L");
+
+        let layout = Mbuf::<M, D, L>::layout(capacity).expect("Mbuf layout overflow");
+        let pointer = unsafe { std::alloc::alloc(layout) };
+
+        let Some(pointer) = NonNull::new(pointer) else {
+            std::alloc::handle_alloc_error(layout)
+        };
+
+        unsafe {
+            Mbuf::<M, D, L>::init_at_ptr(pointer.as_ptr(), metadata, 0);
+        }
+
+        Self {
+            pointer,
+            layout,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Allocates an Mbuf and moves `metadata` and the items of `iter` into it.
+    /// <br>At most `iter.len()` items are taken.
+    /// # Panics
+    /// Panics if `iter.len()` exceeds the range of `L`, or the Mbuf does not fit into the address space.
+    /// <br>If `iter` panics, the items moved so far and `metadata` are dropped.
+    pub fn from_iter<I>(metadata: M, iter: I) -> Self
+    where
+        I: IntoIterator<Item = D>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let iter = iter.into_iter();
+        let capacity = iter.len();
+        let mbuf = Self::allocate(metadata, capacity);
+
+        unsafe {
+            let header = mbuf.pointer.as_ptr() as *mut Mbuf<M, D, L>;
+            let data = mbuf.pointer.as_ptr().add(Mbuf::<M, D, L>::data_offset()) as *mut D;
+
+            // length is only bumped after each write, so a panicking iterator
+            // leaves `mbuf` in a state its Drop implementation can clean up
+            for (index, item) in iter.take(capacity).enumerate() {
+                data.add(index).write(item);
+                (*header).set_len(index + 1);
+            }
+        }
+
+        mbuf
+    }
+
+    /// Allocates an Mbuf and moves `metadata` and the elements of `data` into it.
+    /// # Panics
+    /// Panics if `data.len()` exceeds the range of `L`, or the Mbuf does not fit into the address space.
+    pub fn from_vec(metadata: M, mut data: Vec<D>) -> Self {
+        let mbuf = Self::allocate(metadata, data.len());
+
+        unsafe {
+            let header = mbuf.pointer.as_ptr() as *mut Mbuf<M, D, L>;
+            let target = mbuf.pointer.as_ptr().add(Mbuf::<M, D, L>::data_offset()) as *mut D;
+
+            std::ptr::copy_nonoverlapping(data.as_ptr(), target, data.len());
+            (*header).set_len(data.len());
+            data.set_len(0);
+        }
+
+        mbuf
+    }
+
+    /// Number of bytes allocated for this Mbuf.
+    pub fn allocated_size(&self) -> usize {
+        self.layout.size()
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [D] {
+        self.mbuf_mut()
+    }
+
+    pub fn set_metadata(&mut self, metadata: M) -> M {
+        self.mbuf_mut().set_metadata(metadata)
+    }
+
+    /// The owned Mbuf, which must not be handed out, as swapping it would detach its length from its allocation.
+    fn mbuf_mut(&mut self) -> &mut Mbuf<'static, M, D, L> {
+        unsafe { &mut *(self.pointer.as_ptr() as *mut Mbuf<'static, M, D, L>) }
+    }
+}
+
+impl<M, D: Clone + 'static, L: MbufLength> MbufBox<M, D, L> {
+    /// Allocates an Mbuf and clones `metadata` and `data` into it.
+    /// # Panics
+    /// Panics if `data.len()` exceeds the range of `L`, or the Mbuf does not fit into the address space.
+    pub fn from_slice(metadata: M, data: &[D]) -> Self {
+        Self::from_iter(metadata, data.iter().cloned())
+    }
+}
+
+impl<M, D: 'static, L: MbufLength> std::ops::Deref for MbufBox<M, D, L> {
+    type Target = Mbuf<'static, M, D, L>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { &*(self.pointer.as_ptr() as *const Self::Target) }
+    }
+}
+
+impl<M, D: 'static, L: MbufLength> Drop for MbufBox<M, D, L> {
+    fn drop(&mut self) {
+        unsafe {
+            self.mbuf_mut().drop_in_place();
+
+            std::alloc::dealloc(self.pointer.as_ptr(), self.layout);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn drops_metadata_and_elements() {
+        let counter = Rc::new(());
+        let mut mbuf = MbufBox::<_, _>::from_vec(counter.clone(), vec![counter.clone(); 3]);
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        drop(mbuf.set_metadata(Rc::new(())));
+        mbuf.as_mut_slice()[0] = Rc::new(());
+        assert_eq!(Rc::strong_count(&counter), 3);
+
+        let cloned = MbufBox::<_, _, u8>::from_slice((), &mbuf);
+        assert_eq!(Rc::strong_count(&counter), 5);
+
+        drop(mbuf);
+        drop(cloned);
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    fn panicking_from_iter() {
+        let counter = Rc::new(());
+        let items = (0..4).map(|index| {
+            assert!(index < 2, "iterator panicked");
+            counter.clone()
+        });
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            MbufBox::<_, _>::from_iter(counter.clone(), items)
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(Rc::strong_count(&counter), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "Mbuf length exceeds the range of L")]
+    fn length_exceeds_range() {
+        MbufBox::<u8, u8, u8>::from_vec(0, vec![0; 300]);
+    }
+}
@@ -0,0 +1,208 @@
+use std::alloc::Layout;
+use std::ptr::NonNull;
+
+use crate::{MbufError, Region};
+
+/// A growable, heap-allocated byte buffer whose first byte is aligned to [`AlignedBuffer::ALIGN`] bytes.
+/// <br>Unlike `Vec<u8>`, which only guarantees an alignment of 1, it keeps that alignment when it grows,
+/// so a [`crate::MbufWriter`], [`crate::MbufArena`] or [`crate::MbufHeap`] over it never misaligns
+/// the records written so far. Bytes added by growing are zeroed.
+pub struct AlignedBuffer {
+    pointer: NonNull<u8>,
+    len: usize,
+    capacity: usize,
+}
+
+unsafe impl Send for AlignedBuffer {}
+unsafe impl Sync for AlignedBuffer {}
+
+impl AlignedBuffer {
+    /// Alignment of the first byte, enough for every Mbuf an [`crate::MbufHeap`] can hold.
+    pub const ALIGN: usize = 16;
+
+    /// The largest number of bytes an allocation aligned to [`AlignedBuffer::ALIGN`] can span.
+    const MAX_CAPACITY: usize = isize::MAX as usize + 1 - Self::ALIGN;
+
+    /// Creates an empty buffer without allocating.
+    pub const fn new() -> Self {
+        Self {
+            // a dangling, but aligned pointer
+            pointer: unsafe { NonNull::new_unchecked(Self::ALIGN as *mut u8) },
+            len: 0,
+            capacity: 0,
+        }
+    }
+
+    /// Creates a buffer of `len` zeroed bytes.
+    pub fn zeroed(len: usize) -> Self {
+        let mut buffer = Self::new();
+
+        buffer.resize(len);
+
+        buffer
+    }
+
+    /// Creates a buffer holding a copy of `bytes`.
+    pub fn from_slice(bytes: &[u8]) -> Self {
+        let mut buffer = Self::zeroed(bytes.len());
+
+        buffer.copy_from_slice(bytes);
+
+        buffer
+    }
+
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn as_slice(&self) -> &[u8] {
+        unsafe { std::slice::from_raw_parts(self.pointer.as_ptr(), self.len) }
+    }
+
+    pub fn as_mut_slice(&mut self) -> &mut [u8] {
+        unsafe { std::slice::from_raw_parts_mut(self.pointer.as_ptr(), self.len) }
+    }
+
+    /// Grows the buffer to `len` bytes, zeroing the new ones, or shortens it to `len` bytes.
+    /// # Panics
+    /// Panics if `len` exceeds `isize::MAX` bytes once aligned.
+    pub fn resize(&mut self, len: usize) {
+        if len > self.capacity {
+            let doubled = self.capacity.saturating_mul(2).min(Self::MAX_CAPACITY);
+
+            self.reallocate(len.max(doubled));
+        }
+
+        if len > self.len {
+            unsafe {
+                self.pointer
+                    .as_ptr()
+                    .add(self.len)
+                    .write_bytes(0, len - self.len);
+            }
+        }
+
+        self.len = len;
+    }
+
+    fn reallocate(&mut self, capacity: usize) {
+        let layout = Self::layout(capacity);
+
+        let pointer = unsafe {
+            if self.capacity == 0 {
+                std::alloc::alloc(layout)
+            } else {
+                std::alloc::realloc(self.pointer.as_ptr(), Self::layout(self.capacity), capacity)
+            }
+        };
+
+        let Some(pointer) = NonNull::new(pointer) else {
+            std::alloc::handle_alloc_error(layout)
+        };
+
+        self.pointer = pointer;
+        self.capacity = capacity;
+    }
+
+    fn layout(capacity: usize) -> Layout {
+        Layout::from_size_align(capacity, Self::ALIGN).expect("AlignedBuffer capacity overflow")
+    }
+}
+
+impl Region for AlignedBuffer {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+
+    fn grow(&mut self, size: usize) -> Result<(), MbufError> {
+        if size > Self::MAX_CAPACITY {
+            return Err(MbufError::LengthOverflow { length: size });
+        }
+
+        if size > self.len {
+            self.resize(size);
+        }
+
+        Ok(())
+    }
+}
+
+impl Default for AlignedBuffer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clone for AlignedBuffer {
+    fn clone(&self) -> Self {
+        Self::from_slice(self)
+    }
+}
+
+impl std::fmt::Debug for AlignedBuffer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_slice().fmt(f)
+    }
+}
+
+impl std::ops::Deref for AlignedBuffer {
+    type Target = [u8];
+
+    fn deref(&self) -> &[u8] {
+        self.as_slice()
+    }
+}
+
+impl std::ops::DerefMut for AlignedBuffer {
+    fn deref_mut(&mut self) -> &mut [u8] {
+        self.as_mut_slice()
+    }
+}
+
+impl Drop for AlignedBuffer {
+    fn drop(&mut self) {
+        if self.capacity != 0 {
+            unsafe { std::alloc::dealloc(self.pointer.as_ptr(), Self::layout(self.capacity)) }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn stays_aligned_and_zeroed_when_growing() {
+        let mut buffer = AlignedBuffer::new();
+
+        assert!(buffer.is_empty());
+        assert!((buffer.as_ptr() as usize).is_multiple_of(AlignedBuffer::ALIGN));
+
+        for len in [1, 17, 100, 1000, 10_000] {
+            let previous = buffer.len();
+
+            buffer[previous.saturating_sub(1)..].fill(0xff);
+            buffer.grow(len).unwrap();
+
+            assert_eq!(buffer.len(), len);
+            assert!((buffer.as_ptr() as usize).is_multiple_of(AlignedBuffer::ALIGN));
+            assert!(buffer[previous..].iter().all(|byte| *byte == 0));
+        }
+
+        buffer.resize(3);
+        assert_eq!(buffer.len(), 3);
+        assert_eq!(buffer.clone().as_slice(), buffer.as_slice());
+    }
+}
@@ -0,0 +1,188 @@
+use xxhash_rust::xxh64::Xxh64;
+
+use crate::pod::{bytes_of, bytes_of_slice};
+use crate::{Mbuf, MbufError, MbufLength, MbufMut, Pod, U64Le};
+
+/// Metadata wrapper storing an XXH64 checksum of the wrapped metadata, the length and all elements.
+/// <br>It is packed, so it never contains padding, and the wrapped metadata is accessed by value.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C, packed)]
+pub struct Checksummed<M> {
+    metadata: M,
+    checksum: U64Le,
+}
+
+unsafe impl<M: Pod> Pod for Checksummed<M> {}
+
+impl<M: Pod> Checksummed<M> {
+    /// Wraps `metadata` with a zero checksum, to be stored by [`Mbuf::update_checksum`].
+    pub fn new(metadata: M) -> Self {
+        Self {
+            metadata,
+            checksum: U64Le::default(),
+        }
+    }
+
+    pub fn metadata(&self) -> M {
+        self.metadata
+    }
+
+    pub fn checksum(&self) -> u64 {
+        self.checksum.get()
+    }
+}
+
+impl<'lt, M: Pod, D: Pod, L: MbufLength> Mbuf<'lt, Checksummed<M>, D, L> {
+    /// Computes the checksum over the metadata, the length and all elements.
+    pub fn compute_checksum(&self) -> u64 {
+        let metadata = self.metadata.metadata;
+        let mut hasher = Xxh64::new(0);
+
+        hasher.update(bytes_of(&metadata));
+        hasher.update(bytes_of(&self.length));
+        hasher.update(bytes_of_slice(self.to_slice()));
+
+        hasher.digest()
+    }
+
+    /// Stores the checksum of the current contents.
+    pub fn update_checksum(&mut self) {
+        self.metadata.checksum = U64Le::new(self.compute_checksum());
+    }
+
+    /// Checks the stored checksum against the current contents.
+    pub fn verify(&self) -> Result<(), MbufError> {
+        let expected = self.metadata.checksum();
+        let actual = self.compute_checksum();
+
+        if expected != actual {
+            return Err(MbufError::ChecksumMismatch { expected, actual });
+        }
+
+        Ok(())
+    }
+
+    /// Interprets the beginning of `bytes` as an Mbuf, checking alignment, bounds and checksum.
+    pub fn from_bytes_verified(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        let mbuf = Self::from_bytes(bytes)?;
+
+        mbuf.verify()?;
+
+        Ok(mbuf)
+    }
+
+    /// Interprets the beginning of `bytes` as a mutable Mbuf, checking alignment, bounds and checksum.
+    pub fn from_bytes_verified_mut(
+        bytes: &'lt mut [u8],
+    ) -> Result<MbufMut<'lt, Checksummed<M>, D, L>, MbufError> {
+        let mbuf = Self::from_bytes_mut(bytes)?;
+
+        mbuf.as_mbuf().verify()?;
+
+        Ok(mbuf)
+    }
+
+    /// Declares an Mbuf at `pointer`, copies `metadata` and `data` into it and stores their checksum.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + checksum + `L` + `data`.
+    /// - `pointer` must be aligned to at least `L`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_checksummed_to_ptr(pointer: *mut u8, metadata: M, data: &[D]) -> &'lt Self {
+        let mbuf = Mbuf::write_to_ptr_mut(pointer, Checksummed::new(metadata), data);
+
+        mbuf.update_checksum();
+
+        mbuf
+    }
+
+    /// Copies `metadata` and `data` into the beginning of `bytes` and stores their checksum.
+    pub fn write_checksummed_to_bytes(
+        bytes: &'lt mut [u8],
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufMut<'lt, Checksummed<M>, D, L>, MbufError> {
+        let mut mbuf = Self::write_to_bytes(bytes, Checksummed::new(metadata), data)?;
+
+        mbuf.update_checksum();
+
+        Ok(mbuf)
+    }
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> MbufMut<'_, Checksummed<M>, D, L> {
+    /// Stores the checksum of the current contents.
+    pub fn update_checksum(&mut self) {
+        self.mbuf.update_checksum();
+    }
+
+    /// Replaces the wrapped metadata and stores the checksum of the new contents.
+    pub fn set_metadata_and_update(&mut self, metadata: M) -> M {
+        let previous = self.set_metadata(Checksummed::new(metadata));
+
+        self.update_checksum();
+
+        previous.metadata()
+    }
+}
+
+#[cfg(all(test, feature = "checksum"))]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    type Checked<'lt> = Mbuf<'lt, Checksummed<u32>, u16>;
+
+    #[test]
+    fn verify() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let mut mbuf = Checked::write_checksummed_to_bytes(&mut buffer, 7, &[1, 2, 3]).unwrap();
+
+        assert_eq!(mbuf.as_mbuf().verify(), Ok(()));
+        assert_eq!(mbuf.get_metadata().metadata(), 7);
+
+        mbuf[1] = 5;
+        assert!(matches!(
+            mbuf.as_mbuf().verify(),
+            Err(MbufError::ChecksumMismatch { .. })
+        ));
+
+        mbuf.update_checksum();
+        assert_eq!(mbuf.as_mbuf().verify(), Ok(()));
+        assert_eq!(
+            &**Checked::from_bytes_verified(&buffer).unwrap(),
+            &[1, 5, 3]
+        );
+    }
+
+    #[test]
+    fn rejects_flipped_data_byte() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        Checked::write_checksummed_to_bytes(&mut buffer, 7, &[1, 2, 3]).unwrap();
+
+        buffer[Checked::data_offset() + 2] ^= 0x10;
+
+        let checksum = Checked::from_bytes(&buffer)
+            .unwrap()
+            .get_metadata()
+            .checksum();
+        assert!(matches!(
+            Checked::from_bytes_verified(&buffer),
+            Err(MbufError::ChecksumMismatch { expected, .. }) if expected == checksum
+        ));
+        assert!(Checked::from_bytes_verified_mut(&mut buffer).is_err());
+    }
+
+    #[test]
+    fn set_metadata_and_update() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let mut mbuf = Checked::write_checksummed_to_bytes(&mut buffer, 7, &[1, 2, 3]).unwrap();
+        let checksum = mbuf.get_metadata().checksum();
+
+        assert_eq!(mbuf.set_metadata_and_update(8), 7);
+        assert_ne!(mbuf.get_metadata().checksum(), checksum);
+
+        let mbuf = Checked::from_bytes_verified_mut(&mut buffer).unwrap();
+        assert_eq!(mbuf.get_metadata().metadata(), 8);
+    }
+}
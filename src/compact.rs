@@ -0,0 +1,250 @@
+use crate::heap::{
+    class_size, data_start, size_class, BlockHeader, BLOCK_ALIGN, FREE, SIZE_CLASSES,
+};
+use crate::pod::pod_ref;
+use crate::{MbufError, MbufHeap, MbufLength, MbufOffset, Region};
+
+/// Maps the offsets of Mbufs moved by [`MbufHeap::compact`] to their new offsets.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Relocations {
+    /// Pairs of old and new offsets, sorted by old offset.
+    moves: Vec<(u64, u64)>,
+}
+
+impl Relocations {
+    /// The new offset of the Mbuf previously at `offset`, or `None` if it was not moved.
+    pub fn get(&self, offset: u64) -> Option<u64> {
+        self.moves
+            .binary_search_by_key(&offset, |(old, _)| *old)
+            .ok()
+            .map(|index| self.moves[index].1)
+    }
+
+    /// The current location of the Mbuf previously at `offset`.
+    pub fn relocate<M, D, L>(&self, offset: MbufOffset<M, D, L>) -> MbufOffset<M, D, L> {
+        self.get(offset.get()).map_or(offset, MbufOffset::from_raw)
+    }
+
+    /// Number of Mbufs moved.
+    pub fn len(&self) -> usize {
+        self.moves.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Iterates over pairs of old and new offsets, sorted by old offset.
+    pub fn iter(&self) -> impl Iterator<Item = (u64, u64)> + '_ {
+        self.moves.iter().copied()
+    }
+}
+
+/// Outcome of [`MbufHeap::compact`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CompactionReport {
+    relocations: Relocations,
+    bytes_reclaimed: usize,
+    fixup_errors: Vec<(usize, MbufError)>,
+}
+
+impl CompactionReport {
+    pub fn relocations(&self) -> &Relocations {
+        &self.relocations
+    }
+
+    pub fn into_relocations(self) -> Relocations {
+        self.relocations
+    }
+
+    /// Number of bytes by which the end of the heap moved down.
+    pub fn bytes_reclaimed(&self) -> usize {
+        self.bytes_reclaimed
+    }
+
+    /// Offsets of the Mbufs for which `fixup` failed, along with its errors.
+    pub fn fixup_errors(&self) -> &[(usize, MbufError)] {
+        &self.fixup_errors
+    }
+}
+
+impl<R: Region, L: MbufLength> MbufHeap<R, L> {
+    /// Moves all allocated blocks to the beginning of the heap, in order, dropping all free blocks.
+    /// <br>Afterwards, `fixup` is called with the offset of every allocated Mbuf, so that offsets stored
+    /// inside of it can be updated with [`Relocations::relocate`].
+    /// Offsets held elsewhere must be updated from the returned report.
+    /// <br>If `fixup` fails, the remaining Mbufs are still visited, and its errors are collected in
+    /// [`CompactionReport::fixup_errors`]. An error is only returned if the heap is corrupt,
+    /// in which case nothing is moved.
+    /// <br>The region is not shrunk; bytes past [`crate::HeapHeader::end`] are zeroed.
+    pub fn compact(
+        &mut self,
+        mut fixup: impl FnMut(&mut Self, usize, &Relocations) -> Result<(), MbufError>,
+    ) -> Result<CompactionReport, MbufError> {
+        let end = usize::try_from(self.header()?.end).unwrap_or(usize::MAX);
+
+        if end > self.region().bytes().len() {
+            return Err(MbufError::InvalidMetadata("heap end out of range"));
+        }
+
+        let blocks = self.live_blocks(end)?;
+
+        let mut relocations = Relocations::default();
+        let mut payloads = Vec::with_capacity(blocks.len());
+        let mut cursor = data_start();
+
+        for (block, size) in blocks {
+            if block != cursor {
+                self.bytes_mut().copy_within(block..block + size, cursor);
+
+                relocations
+                    .moves
+                    .push(((block + BLOCK_ALIGN) as u64, (cursor + BLOCK_ALIGN) as u64));
+            }
+
+            payloads.push(cursor + BLOCK_ALIGN);
+            cursor += size;
+        }
+
+        let header = self.header_mut()?;
+
+        header.end = cursor as u64;
+        header.free = [0; SIZE_CLASSES];
+
+        self.bytes_mut()[cursor..end].fill(0);
+
+        let fixup_errors = payloads
+            .into_iter()
+            .filter_map(|payload| {
+                fixup(self, payload, &relocations)
+                    .err()
+                    .map(|err| (payload, err))
+            })
+            .collect();
+
+        Ok(CompactionReport {
+            relocations,
+            bytes_reclaimed: end - cursor,
+            fixup_errors,
+        })
+    }
+
+    /// Walks all blocks up to `end`, returning the offsets and sizes of allocated ones.
+    /// <br>Validates every block header first, so that a corrupt heap is left untouched.
+    fn live_blocks(&self, end: usize) -> Result<Vec<(usize, usize)>, MbufError> {
+        let mut blocks = Vec::new();
+        let mut block = data_start();
+
+        while block < end {
+            let size = pod_ref::<BlockHeader>(self.region().bytes(), block)?.size;
+            let free = size & FREE != 0;
+            let size = (size & !FREE) as usize;
+
+            if size_class(size).is_none_or(|class| class_size(class) != size) || size > end - block
+            {
+                return Err(MbufError::InvalidMetadata("corrupt block header"));
+            }
+
+            if !free {
+                blocks.push((block, size));
+            }
+
+            block += size;
+        }
+
+        Ok(blocks)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlignedBuffer, Pod, U64Le};
+
+    #[derive(Clone, Copy)]
+    #[repr(C)]
+    struct Node {
+        next: MbufOffset<Node, u64>,
+        tag: U64Le,
+    }
+
+    unsafe impl Pod for Node {}
+
+    fn relink(
+        heap: &mut MbufHeap<AlignedBuffer>,
+        payload: usize,
+        relocations: &Relocations,
+    ) -> Result<(), MbufError> {
+        let mut node = heap.get_mut(MbufOffset::<Node, u64>::new(payload))?;
+        let mut metadata = *node.get_metadata();
+
+        metadata.next = relocations.relocate(metadata.next);
+        node.set_metadata(metadata);
+
+        Ok(())
+    }
+
+    #[test]
+    fn compact_and_relocate() {
+        let mut heap = MbufHeap::new(AlignedBuffer::new()).unwrap();
+        let junk = heap.alloc_mbuf::<u8, u8>(0, 100).unwrap();
+        let first = Node {
+            next: MbufOffset::NULL,
+            tag: 1.into(),
+        };
+        let a = heap
+            .alloc_mbuf_from_slice::<Node, u64>(first, &[1, 2])
+            .unwrap();
+        let second = Node {
+            next: a,
+            tag: 2.into(),
+        };
+        let b = heap
+            .alloc_mbuf_from_slice::<Node, u64>(second, &[3])
+            .unwrap();
+
+        heap.free_mbuf(junk).unwrap();
+
+        let end = heap.header().unwrap().end();
+        let report = heap.compact(relink).unwrap();
+
+        assert!(report.bytes_reclaimed() > 0);
+        assert_eq!(
+            report.bytes_reclaimed() as u64,
+            end - heap.header().unwrap().end()
+        );
+        assert!(report.fixup_errors().is_empty());
+
+        let a = report.relocations().relocate(a);
+        let b = report.relocations().relocate(b);
+        let node = heap.get(b).unwrap();
+
+        assert_eq!(&node[..], &[3]);
+        assert_eq!(node.get_metadata().tag.get(), 2);
+        assert_eq!(node.get_metadata().next, a);
+        assert_eq!(&heap.get(a).unwrap()[..], &[1, 2]);
+
+        let again = heap.compact(|_, _, _| Ok(())).unwrap();
+        assert_eq!(again.bytes_reclaimed(), 0);
+        assert!(again.relocations().is_empty());
+    }
+
+    #[test]
+    fn fixup_errors_are_collected() {
+        let mut heap = MbufHeap::new(AlignedBuffer::new()).unwrap();
+        let a = heap.alloc_mbuf::<u8, u8>(0, 1).unwrap();
+        let b = heap.alloc_mbuf::<u8, u8>(0, 1).unwrap();
+        let mut visited = Vec::new();
+
+        let report = heap
+            .compact(|_, payload, _| {
+                visited.push(payload);
+                Err(MbufError::InvalidMetadata("fixup failed"))
+            })
+            .unwrap();
+
+        assert_eq!(visited, [a.get() as usize, b.get() as usize]);
+        assert_eq!(report.fixup_errors().len(), 2);
+        assert_eq!(report.fixup_errors()[1].0, b.get() as usize);
+    }
+}
@@ -0,0 +1,251 @@
+use crate::{Endianness, Mbuf, MbufBox, MbufLength, MbufMut, Pod};
+
+macro_rules! endian_type {
+    ($(#[$doc:meta] $name:ident($t:ty, $to_bytes:ident, $from_bytes:ident);)*) => {
+        $(
+            #[$doc]
+            #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+            #[repr(transparent)]
+            pub struct $name([u8; std::mem::size_of::<$t>()]);
+
+            unsafe impl Pod for $name {}
+
+            impl $name {
+                pub const fn new(value: $t) -> Self {
+                    Self(value.$to_bytes())
+                }
+
+                pub const fn get(self) -> $t {
+                    <$t>::$from_bytes(self.0)
+                }
+
+                pub fn set(&mut self, value: $t) {
+                    *self = Self::new(value);
+                }
+            }
+
+            impl From<$t> for $name {
+                fn from(value: $t) -> Self {
+                    Self::new(value)
+                }
+            }
+
+            impl From<$name> for $t {
+                fn from(value: $name) -> Self {
+                    value.get()
+                }
+            }
+
+            impl std::fmt::Debug for $name {
+                fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                    self.get().fmt(f)
+                }
+            }
+        )*
+    };
+}
+
+endian_type! {
+    /// A little-endian `u16` with an alignment of 1.
+    U16Le(u16, to_le_bytes, from_le_bytes);
+    /// A big-endian `u16` with an alignment of 1.
+    U16Be(u16, to_be_bytes, from_be_bytes);
+    /// A little-endian `u32` with an alignment of 1.
+    U32Le(u32, to_le_bytes, from_le_bytes);
+    /// A big-endian `u32` with an alignment of 1.
+    U32Be(u32, to_be_bytes, from_be_bytes);
+    /// A little-endian `u64` with an alignment of 1.
+    U64Le(u64, to_le_bytes, from_le_bytes);
+    /// A big-endian `u64` with an alignment of 1.
+    U64Be(u64, to_be_bytes, from_be_bytes);
+    /// A little-endian `i16` with an alignment of 1.
+    I16Le(i16, to_le_bytes, from_le_bytes);
+    /// A big-endian `i16` with an alignment of 1.
+    I16Be(i16, to_be_bytes, from_be_bytes);
+    /// A little-endian `i32` with an alignment of 1.
+    I32Le(i32, to_le_bytes, from_le_bytes);
+    /// A big-endian `i32` with an alignment of 1.
+    I32Be(i32, to_be_bytes, from_be_bytes);
+    /// A little-endian `i64` with an alignment of 1.
+    I64Le(i64, to_le_bytes, from_le_bytes);
+    /// A big-endian `i64` with an alignment of 1.
+    I64Be(i64, to_be_bytes, from_be_bytes);
+    /// A little-endian `f32` with an alignment of 1.
+    F32Le(f32, to_le_bytes, from_le_bytes);
+    /// A big-endian `f32` with an alignment of 1.
+    F32Be(f32, to_be_bytes, from_be_bytes);
+    /// A little-endian `f64` with an alignment of 1.
+    F64Le(f64, to_le_bytes, from_le_bytes);
+    /// A big-endian `f64` with an alignment of 1.
+    F64Be(f64, to_be_bytes, from_be_bytes);
+}
+
+macro_rules! endian_length {
+    ($($name:ident($t:ty)),* $(,)?) => {
+        $(
+            unsafe impl MbufLength for $name {
+                fn from_usize(length: usize) -> Option<Self> {
+                    <$t>::try_from(length).ok().map(Self::new)
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    self.get().try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+endian_length!(
+    U16Le(u16),
+    U16Be(u16),
+    U32Le(u32),
+    U32Be(u32),
+    U64Le(u64),
+    U64Be(u64)
+);
+
+/// Types whose byte order can be reversed in place.
+pub trait ByteSwap {
+    fn swap_bytes(&mut self);
+}
+
+macro_rules! impl_byte_swap {
+    ($($t:ty),* $(,)?) => {
+        $(
+            impl ByteSwap for $t {
+                fn swap_bytes(&mut self) {
+                    *self = <$t>::swap_bytes(*self);
+                }
+            }
+        )*
+    };
+}
+
+impl_byte_swap!(u8, u16, u32, u64, u128, usize, i8, i16, i32, i64, i128, isize);
+
+impl ByteSwap for f32 {
+    fn swap_bytes(&mut self) {
+        *self = f32::from_bits(self.to_bits().swap_bytes());
+    }
+}
+
+impl ByteSwap for f64 {
+    fn swap_bytes(&mut self) {
+        *self = f64::from_bits(self.to_bits().swap_bytes());
+    }
+}
+
+impl<T: ByteSwap, const N: usize> ByteSwap for [T; N] {
+    fn swap_bytes(&mut self) {
+        self.iter_mut().for_each(ByteSwap::swap_bytes);
+    }
+}
+
+impl<'lt, M, D: ByteSwap, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// Converts all elements from byte order `from` to byte order `to` in place.
+    /// <br>Metadata and length are left untouched; use endian-explicit types such as
+    /// [`U32Le`] for them if the Mbuf crosses hosts.
+    pub fn convert_endianness(&mut self, from: Endianness, to: Endianness) {
+        convert_endianness(self, from, to);
+    }
+}
+
+impl<M, D: ByteSwap, L: MbufLength> MbufMut<'_, M, D, L> {
+    /// Converts all elements from byte order `from` to byte order `to` in place.
+    pub fn convert_endianness(&mut self, from: Endianness, to: Endianness) {
+        convert_endianness(self, from, to);
+    }
+}
+
+impl<M, D: ByteSwap, L: MbufLength> MbufBox<M, D, L> {
+    /// Converts all elements from byte order `from` to byte order `to` in place.
+    pub fn convert_endianness(&mut self, from: Endianness, to: Endianness) {
+        convert_endianness(self.as_mut_slice(), from, to);
+    }
+}
+
+fn convert_endianness<D: ByteSwap>(data: &mut [D], from: Endianness, to: Endianness) {
+    if from != to {
+        data.iter_mut().for_each(ByteSwap::swap_bytes);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    #[test]
+    fn round_trips() {
+        assert_eq!(U16Le::new(0x1234).0, [0x34, 0x12]);
+        assert_eq!(U16Be::new(0x1234).0, [0x12, 0x34]);
+        assert_eq!(U32Le::new(0x1234_5678).0, [0x78, 0x56, 0x34, 0x12]);
+        assert_eq!(U32Be::new(0x1234_5678).0, [0x12, 0x34, 0x56, 0x78]);
+        assert_eq!(U64Le::new(1).0, [1, 0, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(U64Be::new(1).0, [0, 0, 0, 0, 0, 0, 0, 1]);
+        assert_eq!(I16Le::new(-2).0, [0xfe, 0xff]);
+        assert_eq!(I16Be::new(-2).0, [0xff, 0xfe]);
+        assert_eq!(I32Le::new(-2).get(), -2);
+        assert_eq!(I32Be::new(-2).0, [0xff, 0xff, 0xff, 0xfe]);
+        assert_eq!(I64Le::new(i64::MIN).get(), i64::MIN);
+        assert_eq!(I64Be::new(i64::MIN).0[0], 0x80);
+        assert_eq!(F32Le::new(1.5).0, 1.5f32.to_le_bytes());
+        assert_eq!(F32Be::new(-1.5).get(), -1.5);
+        assert_eq!(F64Le::new(0.25).get(), 0.25);
+        assert_eq!(F64Be::new(0.25).0, 0.25f64.to_be_bytes());
+
+        let mut value = U32Be::from(7);
+        value.set(8);
+        assert_eq!(u32::from(value), 8);
+        assert_eq!(format!("{value:?}"), "8");
+        assert_eq!(std::mem::align_of::<F64Be>(), 1);
+    }
+
+    #[test]
+    fn lengths() {
+        assert_eq!(U32Le::from_usize(5), Some(U32Le::new(5)));
+        if let Ok(length) = usize::try_from(u64::from(u32::MAX) + 1) {
+            assert_eq!(U32Le::from_usize(length), None);
+        }
+        assert_eq!(U32Le::new(u32::MAX).to_usize(), Some(u32::MAX as usize));
+        assert_eq!(U64Be::from_usize(5), Some(U64Be::new(5)));
+        assert_eq!(
+            U64Be::new(u64::MAX).to_usize(),
+            usize::try_from(u64::MAX).ok()
+        );
+
+        let mut buffer = AlignedBuffer::zeroed(64);
+        Mbuf::<u8, u16, U64Be>::write_to_bytes(&mut buffer, 1, &[1, 2, 3]).unwrap();
+        assert_eq!(buffer[1..9], [0, 0, 0, 0, 0, 0, 0, 3]);
+        assert_eq!(
+            Mbuf::<u8, u16, U64Be>::from_bytes(&buffer).unwrap().len(),
+            3
+        );
+
+        Mbuf::<u8, u16, U32Le>::write_to_bytes(&mut buffer, 1, &[1, 2]).unwrap();
+        assert_eq!(buffer[1..5], [2, 0, 0, 0]);
+        assert_eq!(
+            &**Mbuf::<u8, u16, U32Le>::from_bytes(&buffer).unwrap(),
+            &[1, 2]
+        );
+    }
+
+    #[test]
+    fn convert() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let mut mbuf = Mbuf::<u8, u32>::write_to_bytes(&mut buffer, 1, &[1, 0x0102_0304]).unwrap();
+
+        mbuf.convert_endianness(Endianness::Little, Endianness::Little);
+        assert_eq!(&*mbuf, &[1, 0x0102_0304]);
+        mbuf.convert_endianness(Endianness::Little, Endianness::Big);
+        assert_eq!(&*mbuf, &[0x0100_0000, 0x0403_0201]);
+        assert_eq!(*mbuf.get_metadata(), 1);
+
+        let mut boxed = MbufBox::<u8, [f32; 2]>::from_vec(1, vec![[1.5, -2.0]]);
+        boxed.convert_endianness(Endianness::Big, Endianness::Little);
+        assert_eq!(boxed[0][0].to_bits(), 1.5f32.to_bits().swap_bytes());
+        boxed.convert_endianness(Endianness::Little, Endianness::Big);
+        assert_eq!(&**boxed, &[[1.5, -2.0]]);
+    }
+}
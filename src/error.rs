@@ -0,0 +1,98 @@
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum MbufError {
+    /// `address` is not a multiple of `align`.
+    MisalignedPointer { address: usize, align: usize },
+    /// The region holds `available` bytes, but `required` bytes are needed.
+    RegionTooSmall { required: usize, available: usize },
+    /// `length` elements do not fit into the address space.
+    LengthOverflow { length: usize },
+    /// `offset` lies outside of a region of `size` bytes.
+    OffsetOutOfRange { offset: usize, size: usize },
+    /// A stored header or metadata field is inconsistent.
+    InvalidMetadata(&'static str),
+    /// The stored checksum does not match the computed one.
+    ChecksumMismatch { expected: u64, actual: u64 },
+    /// All `capacity` element slots are in use.
+    CapacityExhausted { capacity: usize },
+    /// The bytes are not valid UTF-8 past `valid_up_to`.
+    InvalidUtf8 { valid_up_to: usize },
+    /// A NUL-terminated string contains a NUL byte at `position`.
+    InteriorNul { position: usize },
+    /// A NUL-terminated string does not end with a NUL byte.
+    MissingNul,
+    /// An I/O operation on the storage backing a region failed, with the OS error code if there was one.
+    Io {
+        kind: std::io::ErrorKind,
+        raw_os_error: Option<i32>,
+    },
+}
+
+impl std::fmt::Display for MbufError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MisalignedPointer { address, align } => {
+                write!(f, "address {address:#x} is not aligned to {align} bytes")
+            }
+            Self::RegionTooSmall {
+                required,
+                available,
+            } => write!(
+                f,
+                "region of {available} bytes is too small, {required} bytes are required"
+            ),
+            Self::LengthOverflow { length } => {
+                write!(f, "length {length} overflows the address space")
+            }
+            Self::OffsetOutOfRange { offset, size } => {
+                write!(
+                    f,
+                    "offset {offset} is out of range for a region of {size} bytes"
+                )
+            }
+            Self::InvalidMetadata(reason) => write!(f, "invalid metadata: {reason}"),
+            Self::ChecksumMismatch { expected, actual } => write!(
+                f,
+                "checksum mismatch: expected {expected:#x}, computed {actual:#x}"
+            ),
+            Self::CapacityExhausted { capacity } => {
+                write!(f, "capacity of {capacity} elements is exhausted")
+            }
+            Self::InvalidUtf8 { valid_up_to } => {
+                write!(f, "invalid UTF-8 after {valid_up_to} bytes")
+            }
+            Self::InteriorNul { position } => {
+                write!(f, "interior NUL byte at position {position}")
+            }
+            Self::MissingNul => write!(f, "missing trailing NUL byte"),
+            Self::Io {
+                raw_os_error: Some(code),
+                ..
+            } => write!(f, "I/O error: {}", std::io::Error::from_raw_os_error(*code)),
+            Self::Io { kind, .. } => write!(f, "I/O error: {kind}"),
+        }
+    }
+}
+
+impl std::error::Error for MbufError {}
+
+impl From<std::io::Error> for MbufError {
+    fn from(err: std::io::Error) -> Self {
+        Self::Io {
+            kind: err.kind(),
+            raw_os_error: err.raw_os_error(),
+        }
+    }
+}
+
+impl From<MbufError> for std::io::Error {
+    fn from(err: MbufError) -> Self {
+        match err {
+            MbufError::Io {
+                raw_os_error: Some(code),
+                ..
+            } => Self::from_raw_os_error(code),
+            MbufError::Io { kind, .. } => Self::new(kind, err),
+            _ => Self::new(std::io::ErrorKind::InvalidData, err),
+        }
+    }
+}
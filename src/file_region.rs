@@ -0,0 +1,208 @@
+use std::fs::{File, OpenOptions};
+use std::path::Path;
+
+use memmap2::MmapMut;
+
+use crate::mmap::flush_mbuf;
+use crate::{Mbuf, MbufError, MbufLength, MbufMut, MbufOffset, Pod, Region};
+
+/// A [`Region`] backed by a shared, writable memory map of a file, which grows the file and
+/// remaps it on demand.
+/// <br>Growing may move the mapping, so Mbufs are referred to by [`MbufOffset`] handles,
+/// which stay valid and are resolved with [`FileRegion::get`].
+pub struct FileRegion {
+    file: File,
+    map: MmapMut,
+}
+
+impl FileRegion {
+    /// Opens or creates the file at `path` read-write and maps it.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(path)?;
+
+        Self::from_file(file)
+    }
+
+    /// Maps `file`, which must be open for reading and writing.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn from_file(file: File) -> std::io::Result<Self> {
+        let map = MmapMut::map_mut(&file)?;
+
+        Ok(Self { file, map })
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn file(&self) -> &File {
+        &self.file
+    }
+
+    pub fn get<M: Pod, D: Pod, L: MbufLength>(
+        &self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<&Mbuf<'_, M, D, L>, MbufError> {
+        offset.resolve(&self.map)
+    }
+
+    pub fn get_mut<M: Pod, D: Pod, L: MbufLength>(
+        &mut self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<MbufMut<'_, M, D, L>, MbufError> {
+        offset.resolve_mut(&mut self.map)
+    }
+
+    /// Writes all modified pages to the file, blocking until done.
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.map.flush()
+    }
+
+    /// Starts writing all modified pages to the file, without waiting.
+    pub fn flush_async(&self) -> std::io::Result<()> {
+        self.map.flush_async()
+    }
+
+    /// Writes the pages covered by the metadata, length and data of `mbuf` to the file, blocking until done.
+    /// <br>`mbuf` must lie within this region, e.g. be resolved with [`FileRegion::get`].
+    pub fn flush_range<M, D, L: MbufLength>(
+        &self,
+        mbuf: &Mbuf<'_, M, D, L>,
+    ) -> std::io::Result<()> {
+        flush_mbuf(&self.map, mbuf, false)
+    }
+
+    /// Starts writing the pages covered by the metadata, length and data of `mbuf` to the file, without waiting.
+    pub fn flush_async_range<M, D, L: MbufLength>(
+        &self,
+        mbuf: &Mbuf<'_, M, D, L>,
+    ) -> std::io::Result<()> {
+        flush_mbuf(&self.map, mbuf, true)
+    }
+
+    /// Extends the file to `size` bytes and remaps it; does nothing if it is at least that large.
+    /// <br>On Linux, the mapping is resized with `mremap`, elsewhere the file is mapped anew.
+    pub fn resize(&mut self, size: usize) -> std::io::Result<()> {
+        if size <= self.map.len() {
+            return Ok(());
+        }
+
+        self.file.set_len(size as u64)?;
+
+        unsafe { self.remap(size) }
+    }
+
+    #[cfg(target_os = "linux")]
+    unsafe fn remap(&mut self, size: usize) -> std::io::Result<()> {
+        if self.map.is_empty() {
+            self.map = MmapMut::map_mut(&self.file)?;
+
+            return Ok(());
+        }
+
+        self.map
+            .remap(size, memmap2::RemapOptions::new().may_move(true))
+    }
+
+    #[cfg(not(target_os = "linux"))]
+    unsafe fn remap(&mut self, _size: usize) -> std::io::Result<()> {
+        self.map = MmapMut::map_mut(&self.file)?;
+
+        Ok(())
+    }
+}
+
+impl Region for FileRegion {
+    fn bytes(&self) -> &[u8] {
+        &self.map
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        &mut self.map
+    }
+
+    /// Grows the file to at least `size` bytes, and at least doubles it to amortize remapping.
+    fn grow(&mut self, size: usize) -> Result<(), MbufError> {
+        if size <= self.map.len() {
+            return Ok(());
+        }
+
+        let target = size.max(self.map.len().saturating_mul(2));
+
+        Ok(self.resize(target)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_file::TempFile;
+    use crate::MbufWriter;
+
+    #[test]
+    fn grows_and_reopens() {
+        let temp = TempFile::new();
+        let region = unsafe { FileRegion::open(temp.path()) }.unwrap();
+        assert!(region.is_empty());
+
+        let mut writer = MbufWriter::new(region);
+        let mut offsets = Vec::new();
+        let mut lengths = Vec::new();
+
+        for i in 0..64u32 {
+            let data = vec![i; i as usize * 16];
+            offsets.push(MbufOffset::<u32, u32>::new(
+                writer.append(i, &data).unwrap(),
+            ));
+
+            let len = writer.region().len();
+            if lengths.last() != Some(&len) {
+                lengths.push(len);
+            }
+        }
+
+        assert!(lengths.len() > 3);
+
+        let mut region = writer.into_inner();
+        region.get_mut(offsets[1]).unwrap().set_metadata(100);
+        region.flush().unwrap();
+        drop(region);
+
+        let region = unsafe { FileRegion::open(temp.path()) }.unwrap();
+        assert_eq!(region.file().metadata().unwrap().len(), region.len() as u64);
+
+        for (i, offset) in offsets.into_iter().enumerate() {
+            let mbuf = region.get(offset).unwrap();
+
+            assert_eq!(*mbuf.get_metadata(), if i == 1 { 100 } else { i as u32 });
+            assert_eq!(mbuf.len(), i * 16);
+            assert!(mbuf.iter().all(|value| *value == i as u32));
+        }
+    }
+
+    #[test]
+    fn io_error_keeps_os_error() {
+        let err = MbufError::from(std::io::Error::from_raw_os_error(28));
+
+        assert_eq!(
+            err,
+            MbufError::Io {
+                kind: std::io::Error::from_raw_os_error(28).kind(),
+                raw_os_error: Some(28),
+            }
+        );
+        assert_eq!(std::io::Error::from(err).raw_os_error(), Some(28));
+    }
+}
@@ -0,0 +1,338 @@
+use crate::{Mbuf, MbufError, MbufLength, Pod};
+
+/// An Mbuf<M, D, L> preceded by its `capacity`, which can grow in place up to that capacity.
+/// <br>Dereferences to the contained Mbuf, whose length is the number of initialized elements.
+/// It is modified through a [`GrowableMbufMut`].
+#[repr(C)]
+pub struct GrowableMbuf<'lt, M, D, L: MbufLength = usize> {
+    capacity: L,
+    inner: Mbuf<'lt, M, D, L>,
+}
+
+impl<'lt, M, D, L: MbufLength> GrowableMbuf<'lt, M, D, L> {
+    /// Byte offset of the contained Mbuf.
+    pub const fn mbuf_offset() -> usize {
+        std::mem::offset_of!(Self, inner)
+    }
+
+    /// Alignment a GrowableMbuf<'lt, M, D, L> must be placed at.
+    pub const fn required_align() -> usize {
+        let header = std::mem::align_of::<Self>();
+        let mbuf = Mbuf::<M, D, L>::required_align();
+
+        if header > mbuf {
+            header
+        } else {
+            mbuf
+        }
+    }
+
+    /// Number of bytes a GrowableMbuf<'lt, M, D, L> with room for `capacity` elements spans.
+    pub const fn total_size(capacity: usize) -> Result<usize, MbufError> {
+        let size = match Mbuf::<M, D, L>::total_size(capacity) {
+            Ok(size) => size,
+            Err(err) => return Err(err),
+        };
+
+        match size.checked_add(Self::mbuf_offset()) {
+            Some(size) => Ok(size),
+            None => Err(MbufError::LengthOverflow { length: capacity }),
+        }
+    }
+
+    /// Declares an empty GrowableMbuf with room for `capacity` elements at `pointer`.
+    /// # Safety
+    /// - `pointer` must point to at least [`GrowableMbuf::total_size`] bytes of writable memory.
+    /// - `pointer` must be aligned to at least [`GrowableMbuf::required_align`].
+    /// - The memory region at `pointer` must outlive the returned [`GrowableMbufMut`].
+    pub unsafe fn init_at_ptr(
+        pointer: *mut u8,
+        metadata: M,
+        capacity: usize,
+    ) -> GrowableMbufMut<'lt, M, D, L> {
+        let this = pointer as *mut Self;
+
+        std::ptr::addr_of_mut!((*this).capacity)
+            .write(L::from_usize(capacity).expect("Mbuf capacity exceeds the range of L"));
+        Mbuf::<M, D, L>::init_at_ptr(pointer.add(Self::mbuf_offset()), metadata, 0);
+
+        GrowableMbufMut {
+            growable: &mut *this,
+        }
+    }
+
+    /// Declares a GrowableMbuf begins at a given pointer
+    /// # Safety
+    /// Safe only if the pointer points to a valid GrowableMbuf<'lt, M, D, L> in writable memory.
+    /// - The memory region at `pointer` must outlive the returned [`GrowableMbufMut`].
+    pub unsafe fn at_ptr_mut(pointer: *mut u8) -> GrowableMbufMut<'lt, M, D, L> {
+        GrowableMbufMut {
+            growable: &mut *(pointer as *mut Self),
+        }
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+            .to_usize()
+            .expect("Mbuf capacity exceeds the address space")
+    }
+
+    pub fn remaining_capacity(&self) -> usize {
+        self.capacity() - self.inner.len()
+    }
+
+    fn exhausted(&self) -> MbufError {
+        MbufError::CapacityExhausted {
+            capacity: self.capacity(),
+        }
+    }
+}
+
+impl<'lt, M: Pod, D: Pod, L: MbufLength> GrowableMbuf<'lt, M, D, L> {
+    /// Interprets the beginning of `bytes` as a GrowableMbuf, checking alignment and bounds.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::validate(bytes)?;
+
+        Ok(unsafe { &*(bytes.as_ptr() as *const Self) })
+    }
+
+    /// Interprets the beginning of `bytes` as a mutable GrowableMbuf, checking alignment and bounds.
+    pub fn from_bytes_mut(
+        bytes: &'lt mut [u8],
+    ) -> Result<GrowableMbufMut<'lt, M, D, L>, MbufError> {
+        Self::validate(bytes)?;
+
+        Ok(unsafe { Self::at_ptr_mut(bytes.as_mut_ptr()) })
+    }
+
+    /// Declares an empty GrowableMbuf with room for `capacity` elements at the beginning of `bytes`,
+    /// checking alignment and bounds.
+    pub fn init_in_bytes(
+        bytes: &'lt mut [u8],
+        metadata: M,
+        capacity: usize,
+    ) -> Result<GrowableMbufMut<'lt, M, D, L>, MbufError> {
+        Self::check_fits(bytes, capacity)?;
+
+        Ok(unsafe { Self::init_at_ptr(bytes.as_mut_ptr(), metadata, capacity) })
+    }
+
+    fn validate(bytes: &[u8]) -> Result<(), MbufError> {
+        Self::check_fits(bytes, 0)?;
+
+        let capacity = unsafe { (*(bytes.as_ptr() as *const Self)).capacity };
+        let capacity = capacity
+            .to_usize()
+            .ok_or(MbufError::LengthOverflow { length: usize::MAX })?;
+
+        let inner = Mbuf::<M, D, L>::from_bytes_at(bytes, Self::mbuf_offset())?;
+
+        if inner.len() > capacity {
+            return Err(MbufError::InvalidMetadata("length exceeds capacity"));
+        }
+
+        Self::check_fits(bytes, capacity)
+    }
+
+    fn check_fits(bytes: &[u8], capacity: usize) -> Result<(), MbufError> {
+        let address = bytes.as_ptr() as usize;
+        let alignment = std::mem::align_of::<Self>();
+
+        if !address.is_multiple_of(alignment) {
+            return Err(MbufError::MisalignedPointer {
+                address,
+                align: alignment,
+            });
+        }
+
+        if bytes.len() < std::mem::size_of::<Self>() {
+            return Err(MbufError::RegionTooSmall {
+                required: std::mem::size_of::<Self>(),
+                available: bytes.len(),
+            });
+        }
+
+        if L::from_usize(capacity).is_none() {
+            return Err(MbufError::LengthOverflow { length: capacity });
+        }
+
+        Mbuf::<M, D, L>::check_fits(
+            unsafe { bytes.as_ptr().add(Self::mbuf_offset()) },
+            bytes.len() - Self::mbuf_offset(),
+            capacity,
+        )
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> std::ops::Deref for GrowableMbuf<'lt, M, D, L> {
+    type Target = Mbuf<'lt, M, D, L>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.inner
+    }
+}
+
+/// Mutable access to a [`GrowableMbuf`], as returned by its constructors.
+/// <br>Unlike `&mut GrowableMbuf`, it cannot be used to swap or overwrite the GrowableMbuf itself,
+/// whose capacity must keep describing the memory following it.
+/// <br>Dereferences to the GrowableMbuf; its elements are modified through [`GrowableMbufMut::as_mut_slice`].
+pub struct GrowableMbufMut<'lt, M, D, L: MbufLength = usize> {
+    growable: &'lt mut GrowableMbuf<'lt, M, D, L>,
+}
+
+impl<'lt, M, D, L: MbufLength> GrowableMbufMut<'lt, M, D, L> {
+    pub fn as_mut_slice(&mut self) -> &mut [D] {
+        &mut self.growable.inner
+    }
+
+    pub fn set_metadata(&mut self, metadata: M) -> M {
+        self.growable.inner.set_metadata(metadata)
+    }
+
+    /// Appends `value`, or drops it and returns an error if the capacity is exhausted.
+    pub fn push(&mut self, value: D) -> Result<(), MbufError> {
+        let length = self.growable.inner.len();
+
+        if length >= self.capacity() {
+            return Err(self.exhausted());
+        }
+
+        unsafe {
+            (self.growable.inner.data_pointer() as *mut D)
+                .add(length)
+                .write(value);
+        }
+
+        self.growable.inner.set_len(length + 1);
+
+        Ok(())
+    }
+
+    /// Removes and returns the last element.
+    pub fn pop(&mut self) -> Option<D> {
+        let length = self.growable.inner.len().checked_sub(1)?;
+
+        self.growable.inner.set_len(length);
+
+        Some(unsafe { self.growable.inner.data_pointer().add(length).read() })
+    }
+
+    /// Drops all elements past the first `length`.
+    pub fn truncate(&mut self, length: usize) {
+        let current = self.growable.inner.len();
+
+        if length >= current {
+            return;
+        }
+
+        self.growable.inner.set_len(length);
+
+        unsafe {
+            let tail = (self.growable.inner.data_pointer() as *mut D).add(length);
+
+            std::ptr::drop_in_place(std::ptr::slice_from_raw_parts_mut(tail, current - length));
+        }
+    }
+
+    /// Drops all elements.
+    pub fn clear(&mut self) {
+        self.truncate(0);
+    }
+}
+
+impl<'lt, M, D: Clone, L: MbufLength> GrowableMbufMut<'lt, M, D, L> {
+    /// Appends clones of all elements of `data`, or nothing if they do not fit.
+    pub fn extend_from_slice(&mut self, data: &[D]) -> Result<(), MbufError> {
+        if data.len() > self.remaining_capacity() {
+            return Err(self.exhausted());
+        }
+
+        for value in data {
+            self.push(value.clone())?;
+        }
+
+        Ok(())
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> std::ops::Deref for GrowableMbufMut<'lt, M, D, L> {
+    type Target = GrowableMbuf<'lt, M, D, L>;
+
+    fn deref(&self) -> &Self::Target {
+        self.growable
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::pod::pod_mut;
+    use crate::AlignedBuffer;
+
+    type Growable<'lt> = GrowableMbuf<'lt, u32, u16>;
+
+    #[test]
+    fn push_pop_truncate_clear() {
+        let mut buffer = AlignedBuffer::zeroed(Growable::total_size(4).unwrap());
+        let mut growable = Growable::init_in_bytes(&mut buffer, 7, 4).unwrap();
+
+        assert_eq!(growable.capacity(), 4);
+        assert!(growable.is_empty());
+
+        growable.push(1).unwrap();
+        growable.push(2).unwrap();
+        growable.extend_from_slice(&[3, 4]).unwrap();
+        assert_eq!(&***growable, &[1, 2, 3, 4]);
+        assert_eq!(growable.remaining_capacity(), 0);
+
+        assert_eq!(
+            growable.push(5),
+            Err(MbufError::CapacityExhausted { capacity: 4 })
+        );
+        assert_eq!(growable.pop(), Some(4));
+        assert_eq!(
+            growable.extend_from_slice(&[5, 6]),
+            Err(MbufError::CapacityExhausted { capacity: 4 })
+        );
+        assert_eq!(growable.len(), 3);
+
+        growable.as_mut_slice()[0] = 9;
+        growable.truncate(5);
+        assert_eq!(&***growable, &[9, 2, 3]);
+        growable.truncate(1);
+        assert_eq!(&***growable, &[9]);
+        growable.clear();
+        assert_eq!(growable.pop(), None);
+
+        growable.push(8).unwrap();
+        assert_eq!(growable.set_metadata(6), 7);
+
+        let growable = Growable::from_bytes(&buffer).unwrap();
+        assert_eq!(*growable.get_metadata(), 6);
+        assert_eq!(&***growable, &[8]);
+    }
+
+    #[test]
+    fn validate() {
+        let mut buffer = AlignedBuffer::zeroed(Growable::total_size(4).unwrap());
+        Growable::init_in_bytes(&mut buffer, 7, 4)
+            .unwrap()
+            .extend_from_slice(&[1, 2])
+            .unwrap();
+
+        assert!(matches!(
+            Growable::from_bytes(&buffer[..buffer.len() - 1]),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+        assert!(matches!(
+            Growable::init_in_bytes(&mut buffer[..Growable::total_size(2).unwrap()], 7, 3),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+
+        *pod_mut::<usize>(&mut buffer, 0).unwrap() = 1;
+        assert_eq!(
+            Growable::from_bytes(&buffer).err(),
+            Some(MbufError::InvalidMetadata("length exceeds capacity"))
+        );
+    }
+}
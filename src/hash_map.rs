@@ -0,0 +1,311 @@
+use crate::header::{fnv1a, FNV_OFFSET};
+use crate::pod::{bytes_of, PaddingFree};
+use crate::{Mbuf, MbufError, MbufLength, MbufWriter, Pod, Region, U64Le};
+
+/// Metadata of an [`MbufHashMap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct HashMapHeader {
+    len: u64,
+}
+
+unsafe impl Pod for HashMapHeader {}
+
+/// A slot of an [`MbufHashMap`], holding a key and its value if occupied.
+/// <br>`Bucket<K, V>` must not contain padding, which is checked at compile time when a map is used.
+/// As that cannot be checked for every `K` and `V` up front, it does not implement [`Pod`].
+#[derive(Clone, Copy, Debug)]
+#[repr(C)]
+pub struct Bucket<K, V> {
+    tag: U64Le,
+    key: K,
+    value: V,
+}
+
+unsafe impl<K: Pod, V: Pod> PaddingFree for Bucket<K, V> {
+    const NO_PADDING: () = assert!(
+        std::mem::size_of::<Self>()
+            == std::mem::size_of::<U64Le>() + std::mem::size_of::<K>() + std::mem::size_of::<V>(),
+        "Bucket<K, V> must not contain padding"
+    );
+}
+
+impl<K: Pod, V: Pod> Bucket<K, V> {
+    const EMPTY: Self = unsafe { std::mem::zeroed() };
+
+    pub fn is_occupied(&self) -> bool {
+        self.tag.get() != 0
+    }
+
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+/// A read-only open-addressing hash table stored as an Mbuf<HashMapHeader, Bucket<K, V>, L>.
+/// <br>Keys are hashed over their bytes, so `K`'s equality must agree with its byte representation.
+/// <br>Values can be modified in place through an [`MbufHashMapMut`].
+#[repr(transparent)]
+pub struct MbufHashMap<'lt, K, V, L = usize> {
+    inner: Mbuf<'lt, HashMapHeader, Bucket<K, V>, L>,
+}
+
+impl<'lt, K: Pod + Eq, V: Pod, L: MbufLength> MbufHashMap<'lt, K, V, L> {
+    /// Checks that `mbuf` holds a well-formed hash table.
+    pub fn from_mbuf<'a>(
+        mbuf: &'a Mbuf<'lt, HashMapHeader, Bucket<K, V>, L>,
+    ) -> Result<&'a Self, MbufError> {
+        Self::validate(mbuf)?;
+
+        Ok(unsafe { &*(mbuf as *const Mbuf<'lt, HashMapHeader, Bucket<K, V>, L> as *const Self) })
+    }
+
+    /// Interprets the beginning of `bytes` as an MbufHashMap, checking alignment, bounds and structure.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::from_bytes_at(bytes, 0)
+    }
+
+    /// Interprets the beginning of `bytes` as a mutable MbufHashMap, checking alignment, bounds and structure.
+    pub fn from_bytes_mut(bytes: &'lt mut [u8]) -> Result<MbufHashMapMut<'lt, K, V, L>, MbufError> {
+        Self::from_bytes_at_mut(bytes, 0)
+    }
+
+    /// Interprets the bytes at `offset` in `region` as an MbufHashMap, checking alignment, bounds and structure.
+    pub fn from_bytes_at(region: &'lt [u8], offset: usize) -> Result<&'lt Self, MbufError> {
+        let () = Bucket::<K, V>::NO_PADDING;
+
+        // the fields of a bucket are Pod, and it has no padding
+        Self::from_mbuf(unsafe { Mbuf::from_bytes_at_unchecked(region, offset)? })
+    }
+
+    /// Interprets the bytes at `offset` in `region` as a mutable MbufHashMap, checking alignment, bounds and structure.
+    pub fn from_bytes_at_mut(
+        region: &'lt mut [u8],
+        offset: usize,
+    ) -> Result<MbufHashMapMut<'lt, K, V, L>, MbufError> {
+        let () = Bucket::<K, V>::NO_PADDING;
+
+        // the fields of a bucket are Pod, and it has no padding
+        let mbuf = unsafe { Mbuf::from_bytes_at_mut_unchecked(region, offset)? }.mbuf;
+
+        Self::validate(mbuf)?;
+
+        Ok(MbufHashMapMut {
+            map: unsafe {
+                &mut *(mbuf as *mut Mbuf<'lt, HashMapHeader, Bucket<K, V>, L> as *mut Self)
+            },
+        })
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.inner.get_metadata().len as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Number of buckets.
+    pub fn capacity(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.find(key).map(|index| &self.inner[index].value)
+    }
+
+    pub fn contains_key(&self, key: &K) -> bool {
+        self.find(key).is_some()
+    }
+
+    /// Iterates over all entries in bucket order.
+    pub fn iter(&self) -> impl Iterator<Item = (&K, &V)> {
+        self.inner
+            .iter()
+            .filter(|bucket| bucket.is_occupied())
+            .map(|bucket| (&bucket.key, &bucket.value))
+    }
+
+    fn find(&self, key: &K) -> Option<usize> {
+        let () = Bucket::<K, V>::NO_PADDING;
+        let mask = self.capacity() - 1;
+        let tag = tag_of(key);
+        let mut index = tag as usize & mask;
+
+        for _ in 0..self.capacity() {
+            let bucket = &self.inner[index];
+
+            if !bucket.is_occupied() {
+                return None;
+            }
+
+            if bucket.tag.get() == tag && bucket.key == *key {
+                return Some(index);
+            }
+
+            index = (index + 1) & mask;
+        }
+
+        None
+    }
+
+    fn validate(mbuf: &Mbuf<'lt, HashMapHeader, Bucket<K, V>, L>) -> Result<(), MbufError> {
+        let () = Bucket::<K, V>::NO_PADDING;
+
+        if !mbuf.len().is_power_of_two() {
+            return Err(MbufError::InvalidMetadata(
+                "bucket count is not a power of two",
+            ));
+        }
+
+        if mbuf.get_metadata().len > mbuf.len() as u64 {
+            return Err(MbufError::InvalidMetadata("length exceeds capacity"));
+        }
+
+        Ok(())
+    }
+}
+
+/// Mutable access to the values of an [`MbufHashMap`], as returned by its constructors.
+/// <br>Unlike `&mut MbufHashMap`, it cannot be used to swap or overwrite the map itself,
+/// whose bucket count must keep describing the memory following it.
+pub struct MbufHashMapMut<'lt, K, V, L = usize> {
+    map: &'lt mut MbufHashMap<'lt, K, V, L>,
+}
+
+impl<K: Pod + Eq, V: Pod, L: MbufLength> MbufHashMapMut<'_, K, V, L> {
+    pub fn get_mut(&mut self, key: &K) -> Option<&mut V> {
+        self.map
+            .find(key)
+            .map(|index| &mut self.map.inner[index].value)
+    }
+}
+
+impl<'lt, K, V, L> std::ops::Deref for MbufHashMapMut<'lt, K, V, L> {
+    type Target = MbufHashMap<'lt, K, V, L>;
+
+    fn deref(&self) -> &Self::Target {
+        self.map
+    }
+}
+
+/// Hash of `key`, never 0, which marks empty buckets.
+fn tag_of<K: Pod>(key: &K) -> u64 {
+    fnv1a(FNV_OFFSET, bytes_of(key)).max(1)
+}
+
+impl<R: Region, L: MbufLength> MbufWriter<R, L> {
+    /// Appends an [`MbufHashMap`] holding `entries`, returning its offset within the region.
+    /// <br>If a key occurs more than once, its last value is kept.
+    pub fn append_hash_map<K: Pod + Eq, V: Pod>(
+        &mut self,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<usize, MbufError> {
+        let () = Bucket::<K, V>::NO_PADDING;
+        let entries: Vec<(K, V)> = entries.into_iter().collect();
+
+        // keep the load factor at or below 7/8
+        let capacity = entries
+            .len()
+            .checked_mul(8)
+            .map(|slots| slots / 7 + 1)
+            .and_then(usize::checked_next_power_of_two)
+            .ok_or(MbufError::LengthOverflow {
+                length: entries.len(),
+            })?;
+
+        let mask = capacity - 1;
+        let mut buckets = vec![Bucket::<K, V>::EMPTY; capacity];
+        let mut len = 0;
+
+        for (key, value) in entries {
+            let tag = tag_of(&key);
+            let mut index = tag as usize & mask;
+
+            loop {
+                let bucket = &mut buckets[index];
+
+                if !bucket.is_occupied() {
+                    *bucket = Bucket {
+                        tag: U64Le::new(tag),
+                        key,
+                        value,
+                    };
+                    len += 1;
+                    break;
+                }
+
+                if bucket.tag.get() == tag && bucket.key == key {
+                    bucket.value = value;
+                    break;
+                }
+
+                index = (index + 1) & mask;
+            }
+        }
+
+        self.append_padding_free(HashMapHeader { len }, &buckets)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::tests::write_after_record;
+    use crate::AlignedBuffer;
+
+    fn write(entries: &[(u32, u32)]) -> (AlignedBuffer, usize) {
+        write_after_record(|writer| writer.append_hash_map(entries.iter().copied()))
+    }
+
+    #[test]
+    fn round_trip() {
+        let entries: Vec<(u32, u32)> = (0..100).map(|key| (key, key * 3)).collect();
+        let (buffer, offset) = write(&entries);
+        let map = MbufHashMap::<u32, u32>::from_bytes_at(&buffer, offset).unwrap();
+
+        assert_eq!(map.len(), 100);
+        assert!(map.capacity() * 7 >= map.len() * 8);
+        for (key, value) in &entries {
+            assert_eq!(map.get(key), Some(value));
+        }
+        assert_eq!(map.get(&100), None);
+        assert_eq!(map.iter().count(), 100);
+    }
+
+    #[test]
+    fn empty() {
+        let (buffer, offset) = write(&[]);
+        let map = MbufHashMap::<u32, u32>::from_bytes_at(&buffer, offset).unwrap();
+
+        assert!(map.is_empty());
+        assert_eq!(map.get(&0), None);
+        assert_eq!(map.iter().count(), 0);
+    }
+
+    #[test]
+    fn duplicate_keys_keep_last_value() {
+        let (buffer, offset) = write(&[(1, 10), (2, 20), (1, 11)]);
+        let map = MbufHashMap::<u32, u32>::from_bytes_at(&buffer, offset).unwrap();
+
+        assert_eq!(map.len(), 2);
+        assert_eq!(map.get(&1), Some(&11));
+        assert_eq!(map.get(&2), Some(&20));
+    }
+
+    #[test]
+    fn get_mut() {
+        let (mut buffer, offset) = write(&[(1, 10), (2, 20)]);
+        let mut map = MbufHashMap::<u32, u32>::from_bytes_at_mut(&mut buffer, offset).unwrap();
+
+        *map.get_mut(&2).unwrap() = 21;
+        assert!(map.get_mut(&3).is_none());
+        assert_eq!(map.get(&2), Some(&21));
+        assert_eq!(map.get(&1), Some(&10));
+    }
+}
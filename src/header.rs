@@ -0,0 +1,291 @@
+use crate::pod::{pod_mut, pod_ref};
+use crate::{align_up, Mbuf, MbufError, MbufLength, MbufMut, Pod};
+
+/// Byte order of the host that wrote a region.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[repr(u32)]
+pub enum Endianness {
+    Little = 1,
+    Big = 2,
+}
+
+impl Endianness {
+    #[cfg(target_endian = "little")]
+    pub const NATIVE: Self = Self::Little;
+    #[cfg(target_endian = "big")]
+    pub const NATIVE: Self = Self::Big;
+}
+
+/// Identifies the contents of a region holding Mbuf<M, D, L> records.
+/// <br>It is stored at the beginning of the region, the first record follows at [`RegionHeader::data_offset`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct RegionHeader {
+    magic: [u8; 8],
+    version: u32,
+    endianness: u32,
+    length_size: u32,
+    length_align: u32,
+    metadata_size: u32,
+    metadata_align: u32,
+    data_size: u32,
+    data_align: u32,
+    fingerprint: u64,
+}
+
+unsafe impl Pod for RegionHeader {}
+
+impl RegionHeader {
+    pub const MAGIC: [u8; 8] = *b"PS-MBUF\0";
+    pub const VERSION: u32 = 1;
+
+    /// Describes Mbuf<M, D, L> on this host, fingerprinted by [`RegionHeader::type_fingerprint`].
+    pub fn new<M, D, L: MbufLength>() -> Self {
+        Self::with_fingerprint::<M, D, L>(Self::type_fingerprint::<M, D, L>())
+    }
+
+    /// Describes Mbuf<M, D, L> on this host, with a caller-chosen type fingerprint.
+    pub fn with_fingerprint<M, D, L: MbufLength>(fingerprint: u64) -> Self {
+        Self {
+            magic: Self::MAGIC,
+            version: Self::VERSION,
+            endianness: Endianness::NATIVE as u32,
+            length_size: std::mem::size_of::<L>() as u32,
+            length_align: std::mem::align_of::<L>() as u32,
+            metadata_size: std::mem::size_of::<M>() as u32,
+            metadata_align: std::mem::align_of::<M>() as u32,
+            data_size: std::mem::size_of::<D>() as u32,
+            data_align: std::mem::align_of::<D>() as u32,
+            fingerprint,
+        }
+    }
+
+    /// Hashes the type names of `M`, `D` and `L`.
+    /// <br>Type names are not guaranteed to be stable across compiler versions;
+    /// use [`RegionHeader::with_fingerprint`] where that matters.
+    pub fn type_fingerprint<M, D, L>() -> u64 {
+        let hash = fnv1a(FNV_OFFSET, std::any::type_name::<M>().as_bytes());
+        let hash = fnv1a(hash, b",");
+
+        let hash = fnv1a(hash, std::any::type_name::<D>().as_bytes());
+        let hash = fnv1a(hash, b",");
+
+        fnv1a(hash, std::any::type_name::<L>().as_bytes())
+    }
+
+    pub fn fingerprint(&self) -> u64 {
+        self.fingerprint
+    }
+
+    /// Checks that this header was written for Mbuf<M, D, L> by a compatible host.
+    pub fn verify<M, D, L: MbufLength>(&self) -> Result<(), MbufError> {
+        self.verify_fingerprint::<M, D, L>(Self::type_fingerprint::<M, D, L>())
+    }
+
+    /// Checks that this header was written for Mbuf<M, D, L> with the given fingerprint by a compatible host.
+    pub fn verify_fingerprint<M, D, L: MbufLength>(
+        &self,
+        fingerprint: u64,
+    ) -> Result<(), MbufError> {
+        let expected = Self::with_fingerprint::<M, D, L>(fingerprint);
+
+        if self.magic != expected.magic {
+            return Err(MbufError::InvalidMetadata("bad magic bytes"));
+        }
+
+        // the other fields are in the writer's byte order, so this must be checked first;
+        // a header from a host of the other byte order holds one of the byte-swapped values
+        if self.endianness != expected.endianness {
+            let foreign =
+                [Endianness::Little, Endianness::Big].map(|order| (order as u32).swap_bytes());
+
+            return Err(MbufError::InvalidMetadata(
+                if foreign.contains(&self.endianness) {
+                    "endianness mismatch"
+                } else {
+                    "unknown endianness"
+                },
+            ));
+        }
+
+        if self.version != expected.version {
+            return Err(MbufError::InvalidMetadata("unsupported format version"));
+        }
+
+        if (self.length_size, self.length_align) != (expected.length_size, expected.length_align) {
+            return Err(MbufError::InvalidMetadata("length layout mismatch"));
+        }
+
+        if (self.metadata_size, self.metadata_align)
+            != (expected.metadata_size, expected.metadata_align)
+        {
+            return Err(MbufError::InvalidMetadata("metadata layout mismatch"));
+        }
+
+        if (self.data_size, self.data_align) != (expected.data_size, expected.data_align) {
+            return Err(MbufError::InvalidMetadata("element layout mismatch"));
+        }
+
+        if self.fingerprint != expected.fingerprint {
+            return Err(MbufError::InvalidMetadata("type fingerprint mismatch"));
+        }
+
+        Ok(())
+    }
+
+    /// Reads the header at the beginning of `region` and verifies it for Mbuf<M, D, L>.
+    pub fn read<M, D, L: MbufLength>(region: &[u8]) -> Result<&Self, MbufError> {
+        let header: &Self = pod_ref(region, 0)?;
+
+        header.verify::<M, D, L>()?;
+
+        Ok(header)
+    }
+
+    /// Writes this header to the beginning of `region`.
+    pub fn write(&self, region: &mut [u8]) -> Result<(), MbufError> {
+        *pod_mut(region, 0)? = *self;
+
+        Ok(())
+    }
+
+    /// Offset of the first Mbuf<M, D, L> following the header.
+    pub const fn data_offset<M, D, L: MbufLength>() -> usize {
+        align_up(
+            std::mem::size_of::<Self>(),
+            Mbuf::<M, D, L>::required_align(),
+        )
+    }
+}
+
+pub(crate) const FNV_OFFSET: u64 = 0xcbf2_9ce4_8422_2325;
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+
+/// 64-bit FNV-1a hash of `bytes`, continuing from `hash`.
+pub(crate) fn fnv1a(mut hash: u64, bytes: &[u8]) -> u64 {
+    for byte in bytes {
+        hash ^= u64::from(*byte);
+        hash = hash.wrapping_mul(FNV_PRIME);
+    }
+
+    hash
+}
+
+impl<'lt, M: Pod, D: Pod, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// Verifies the [`RegionHeader`] at the beginning of `region` and returns the Mbuf following it.
+    pub fn from_bytes_with_header(region: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        RegionHeader::read::<M, D, L>(region)?;
+
+        Self::from_bytes_at(region, RegionHeader::data_offset::<M, D, L>())
+    }
+
+    /// Verifies the [`RegionHeader`] at the beginning of `region` and returns the mutable Mbuf following it.
+    pub fn from_bytes_with_header_mut(
+        region: &'lt mut [u8],
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        RegionHeader::read::<M, D, L>(region)?;
+
+        Self::from_bytes_at_mut(region, RegionHeader::data_offset::<M, D, L>())
+    }
+
+    /// Writes a [`RegionHeader`] to the beginning of `region`, followed by `metadata` and `data`.
+    pub fn write_to_bytes_with_header(
+        region: &'lt mut [u8],
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        RegionHeader::new::<M, D, L>().write(region)?;
+
+        Self::write_to_bytes_at(
+            region,
+            RegionHeader::data_offset::<M, D, L>(),
+            metadata,
+            data,
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    fn corrupted(corrupt: impl FnOnce(&mut RegionHeader)) -> Result<(), MbufError> {
+        let mut header = RegionHeader::new::<u32, u16, usize>();
+
+        corrupt(&mut header);
+
+        header.verify::<u32, u16, usize>()
+    }
+
+    #[test]
+    fn round_trip() {
+        let mut buffer = AlignedBuffer::zeroed(128);
+        Mbuf::<u32, u16>::write_to_bytes_with_header(&mut buffer, 7, &[1, 2]).unwrap();
+
+        let mbuf = Mbuf::<u32, u16>::from_bytes_with_header(&buffer).unwrap();
+        assert_eq!(*mbuf.get_metadata(), 7);
+        assert_eq!(&**mbuf, &[1, 2]);
+
+        assert_eq!(
+            Mbuf::<u32, u32>::from_bytes_with_header(&buffer).err(),
+            Some(MbufError::InvalidMetadata("element layout mismatch"))
+        );
+    }
+
+    #[test]
+    fn rejects_corrupted_fields() {
+        let mismatch = |reason| Err(MbufError::InvalidMetadata(reason));
+
+        assert_eq!(corrupted(|_| {}), Ok(()));
+        assert_eq!(corrupted(|h| h.magic[0] ^= 1), mismatch("bad magic bytes"));
+        assert_eq!(
+            corrupted(|h| h.version += 1),
+            mismatch("unsupported format version")
+        );
+        assert_eq!(
+            corrupted(|h| h.length_size = 4),
+            mismatch("length layout mismatch")
+        );
+        assert_eq!(
+            corrupted(|h| h.metadata_align = 8),
+            mismatch("metadata layout mismatch")
+        );
+        assert_eq!(
+            corrupted(|h| h.data_size = 4),
+            mismatch("element layout mismatch")
+        );
+        assert_eq!(
+            corrupted(|h| h.fingerprint ^= 1),
+            mismatch("type fingerprint mismatch")
+        );
+        assert_eq!(
+            corrupted(|h| h.endianness = 3),
+            mismatch("unknown endianness")
+        );
+    }
+
+    #[test]
+    fn rejects_foreign_endianness() {
+        let mut buffer = AlignedBuffer::zeroed(128);
+        Mbuf::<u32, u16>::write_to_bytes_with_header(&mut buffer, 7, &[1, 2]).unwrap();
+
+        // a header written by a host of the other byte order
+        let endianness = pod_mut::<u32>(&mut buffer, 12).unwrap();
+        *endianness = endianness.swap_bytes();
+
+        assert_eq!(
+            RegionHeader::read::<u32, u16, usize>(&buffer).err(),
+            Some(MbufError::InvalidMetadata("endianness mismatch"))
+        );
+    }
+
+    #[test]
+    fn custom_fingerprint() {
+        let header = RegionHeader::with_fingerprint::<u32, u16, usize>(42);
+
+        assert_eq!(header.fingerprint(), 42);
+        assert_eq!(header.verify_fingerprint::<u32, u16, usize>(42), Ok(()));
+        assert!(header.verify::<u32, u16, usize>().is_err());
+    }
+}
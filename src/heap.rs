@@ -0,0 +1,452 @@
+use std::marker::PhantomData;
+
+use crate::pod::{pod_mut, pod_ref};
+use crate::{align_up, Mbuf, MbufError, MbufLength, MbufMut, MbufOffset, Pod, Region};
+
+/// Blocks start at multiples of this, and their payloads follow their [`BlockHeader`] directly.
+pub(crate) const BLOCK_ALIGN: usize = 16;
+
+/// The smallest block spans `1 << MIN_CLASS_SHIFT` bytes.
+const MIN_CLASS_SHIFT: u32 = 5;
+
+/// Number of size classes; class `c` holds blocks of `1 << (c + MIN_CLASS_SHIFT)` bytes.
+pub(crate) const SIZE_CLASSES: usize = 40;
+
+/// Set in [`BlockHeader::size`] while the block is on a free list.
+pub(crate) const FREE: u64 = 1;
+
+/// Stored at the beginning of a region managed by an [`MbufHeap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct HeapHeader {
+    magic: [u8; 8],
+    version: u32,
+    reserved: u32,
+    pub(crate) end: u64,
+    pub(crate) free: [u64; SIZE_CLASSES],
+}
+
+unsafe impl Pod for HeapHeader {}
+
+impl HeapHeader {
+    pub const MAGIC: [u8; 8] = *b"PS-MHEAP";
+    pub const VERSION: u32 = 1;
+
+    /// Offset past the last block.
+    pub fn end(&self) -> u64 {
+        self.end
+    }
+}
+
+/// Precedes every block of an [`MbufHeap`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub(crate) struct BlockHeader {
+    /// Size of the block including this header, with [`FREE`] set while the block is free.
+    pub(crate) size: u64,
+    /// Offset of the next block on the same free list, or 0.
+    pub(crate) next: u64,
+}
+
+unsafe impl Pod for BlockHeader {}
+
+/// Allocator placing Mbufs with lengths stored as `L` into a [`Region`], with support for freeing
+/// and resizing them.
+/// <br>Blocks are rounded up to powers of two, and freed blocks are kept on one free list per size class.
+/// All state lives in a [`HeapHeader`] at the beginning of the region, so the heap can be reopened
+/// with [`MbufHeap::open`].
+/// <br>The first byte of the region must be aligned to 16 bytes, as that of an [`crate::AlignedBuffer`] is,
+/// and Mbufs aligned to more cannot be allocated.
+pub struct MbufHeap<R, L = usize> {
+    region: R,
+    _marker: PhantomData<L>,
+}
+
+impl<R: Region> MbufHeap<R> {
+    /// Creates an empty heap at the beginning of `region`, overwriting its contents.
+    pub fn new(mut region: R) -> Result<Self, MbufError> {
+        let start = data_start();
+
+        if region.bytes().len() < start {
+            region.grow(start)?;
+        }
+
+        region.bytes_mut()[..start].fill(0);
+
+        *pod_mut(region.bytes_mut(), 0)? = HeapHeader {
+            magic: HeapHeader::MAGIC,
+            version: HeapHeader::VERSION,
+            reserved: 0,
+            end: start as u64,
+            free: [0; SIZE_CLASSES],
+        };
+
+        Ok(Self {
+            region,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Reopens the heap stored in `region`.
+    pub fn open(region: R) -> Result<Self, MbufError> {
+        let header: &HeapHeader = pod_ref(region.bytes(), 0)?;
+
+        if header.magic != HeapHeader::MAGIC {
+            return Err(MbufError::InvalidMetadata("bad magic bytes"));
+        }
+
+        if header.version != HeapHeader::VERSION {
+            return Err(MbufError::InvalidMetadata("unsupported format version"));
+        }
+
+        let end = usize::try_from(header.end).unwrap_or(usize::MAX);
+
+        if end < data_start() || end > region.bytes().len() || !end.is_multiple_of(BLOCK_ALIGN) {
+            return Err(MbufError::InvalidMetadata("heap end out of range"));
+        }
+
+        Ok(Self {
+            region,
+            _marker: PhantomData,
+        })
+    }
+}
+
+impl<R: Region, L: MbufLength> MbufHeap<R, L> {
+    /// Converts this heap into one which stores lengths as `T`.
+    pub fn with_length_type<T: MbufLength>(self) -> MbufHeap<R, T> {
+        MbufHeap {
+            region: self.region,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn region(&self) -> &R {
+        &self.region
+    }
+
+    pub fn into_inner(self) -> R {
+        self.region
+    }
+
+    pub(crate) fn bytes_mut(&mut self) -> &mut [u8] {
+        self.region.bytes_mut()
+    }
+
+    /// Allocates an Mbuf holding `metadata` and `length` zeroed elements.
+    pub fn alloc_mbuf<M: Pod, D: Pod>(
+        &mut self,
+        metadata: M,
+        length: usize,
+    ) -> Result<MbufOffset<M, D, L>, MbufError> {
+        let size = Self::block_size::<M, D>(length)?;
+        let block = self.alloc_block(size)?;
+        let payload = block + BLOCK_ALIGN;
+
+        let bytes = self.region.bytes_mut();
+        let address = bytes.as_ptr() as usize + payload;
+        let alignment = Mbuf::<M, D, L>::required_align();
+
+        if !address.is_multiple_of(alignment) {
+            self.release_block(block)?;
+
+            return Err(MbufError::MisalignedPointer {
+                address,
+                align: alignment,
+            });
+        }
+
+        bytes[payload..block + size].fill(0);
+
+        unsafe {
+            Mbuf::<M, D, L>::init_at_ptr(bytes.as_mut_ptr().add(payload), metadata, length);
+        }
+
+        Ok(MbufOffset::new(payload))
+    }
+
+    /// Allocates an Mbuf holding `metadata` and a copy of `data`.
+    pub fn alloc_mbuf_from_slice<M: Pod, D: Pod>(
+        &mut self,
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufOffset<M, D, L>, MbufError> {
+        let offset = self.alloc_mbuf(metadata, data.len())?;
+
+        self.get_mut(offset)?.copy_from_slice(data);
+
+        Ok(offset)
+    }
+
+    /// Returns the block of the Mbuf at `offset` to its free list.
+    pub fn free_mbuf<M, D>(&mut self, offset: MbufOffset<M, D, L>) -> Result<(), MbufError> {
+        let (block, _) = self.used_block(offset.get())?;
+
+        self.release_block(block)
+    }
+
+    /// Resizes the Mbuf at `offset` to `length` elements, zeroing new ones.
+    /// <br>It is resized in place if its block is large enough, otherwise it is moved to a new block
+    /// and the old one is freed. Returns the offset of the resized Mbuf.
+    pub fn realloc_mbuf<M: Pod, D: Pod>(
+        &mut self,
+        offset: MbufOffset<M, D, L>,
+        length: usize,
+    ) -> Result<MbufOffset<M, D, L>, MbufError> {
+        let (_, block_size) = self.used_block(offset.get())?;
+        let mbuf = self.get(offset)?;
+        let old_length = mbuf.len();
+        let metadata = *mbuf.get_metadata();
+
+        let payload = offset.get() as usize;
+        let data = payload + Mbuf::<M, D, L>::data_offset();
+        let element = std::mem::size_of::<D>();
+
+        if Self::block_size::<M, D>(length)? <= block_size {
+            if length > old_length {
+                self.region.bytes_mut()[data + old_length * element..data + length * element]
+                    .fill(0);
+            }
+
+            self.get_mut(offset)?.mbuf.set_len(length);
+
+            return Ok(offset);
+        }
+
+        let moved = self.alloc_mbuf::<M, D>(metadata, length)?;
+        let target = moved.get() as usize + Mbuf::<M, D, L>::data_offset();
+
+        self.region
+            .bytes_mut()
+            .copy_within(data..data + old_length.min(length) * element, target);
+
+        self.free_mbuf(offset)?;
+
+        Ok(moved)
+    }
+
+    pub fn get<M: Pod, D: Pod>(
+        &self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<&Mbuf<'_, M, D, L>, MbufError> {
+        offset.resolve(self.region.bytes())
+    }
+
+    pub fn get_mut<M: Pod, D: Pod>(
+        &mut self,
+        offset: MbufOffset<M, D, L>,
+    ) -> Result<MbufMut<'_, M, D, L>, MbufError> {
+        offset.resolve_mut(self.region.bytes_mut())
+    }
+
+    /// Number of bytes a block holding an Mbuf<M, D, L> of `length` elements needs, before rounding.
+    fn block_size<M, D>(length: usize) -> Result<usize, MbufError> {
+        if L::from_usize(length).is_none() {
+            return Err(MbufError::LengthOverflow { length });
+        }
+
+        Mbuf::<M, D, L>::total_size(length)?
+            .checked_add(BLOCK_ALIGN)
+            .ok_or(MbufError::LengthOverflow { length })
+    }
+
+    /// Takes a block of at least `size` bytes from its free list, or from the end of the heap.
+    fn alloc_block(&mut self, size: usize) -> Result<usize, MbufError> {
+        let class = size_class(size).ok_or(MbufError::LengthOverflow { length: size })?;
+        let class_size = class_size(class);
+        let head = self.header()?.free[class];
+
+        let block = if head != 0 {
+            let block = usize::try_from(head).unwrap_or(usize::MAX);
+            let end = usize::try_from(self.header()?.end)
+                .unwrap_or(usize::MAX)
+                .min(self.region.bytes().len());
+
+            if block < data_start()
+                || !block.is_multiple_of(BLOCK_ALIGN)
+                || block.checked_add(class_size).is_none_or(|tail| tail > end)
+            {
+                return Err(MbufError::InvalidMetadata("corrupt free list"));
+            }
+
+            let header = *pod_ref::<BlockHeader>(self.region.bytes(), block)?;
+
+            if header.size != class_size as u64 | FREE {
+                return Err(MbufError::InvalidMetadata("corrupt free list"));
+            }
+
+            self.header_mut()?.free[class] = header.next;
+
+            block
+        } else {
+            let block = self.header()?.end as usize;
+            let end = block
+                .checked_add(class_size)
+                .ok_or(MbufError::LengthOverflow { length: size })?;
+
+            if end > self.region.bytes().len() {
+                self.region.grow(end)?;
+            }
+
+            self.header_mut()?.end = end as u64;
+
+            block
+        };
+
+        *pod_mut(self.region.bytes_mut(), block)? = BlockHeader {
+            size: class_size as u64,
+            next: 0,
+        };
+
+        Ok(block)
+    }
+
+    /// Pushes the allocated block at `block` onto its free list.
+    fn release_block(&mut self, block: usize) -> Result<(), MbufError> {
+        let size = pod_ref::<BlockHeader>(self.region.bytes(), block)?.size;
+        let class =
+            size_class(size as usize).ok_or(MbufError::InvalidMetadata("corrupt block header"))?;
+        let next = self.header()?.free[class];
+
+        *pod_mut(self.region.bytes_mut(), block)? = BlockHeader {
+            size: size | FREE,
+            next,
+        };
+
+        self.header_mut()?.free[class] = block as u64;
+
+        Ok(())
+    }
+
+    /// Finds the allocated block whose payload starts at `payload`, returning its offset and size.
+    fn used_block(&self, payload: u64) -> Result<(usize, usize), MbufError> {
+        let payload = usize::try_from(payload).unwrap_or(usize::MAX);
+        let end = self.header()?.end as usize;
+
+        if payload < data_start() + BLOCK_ALIGN || payload >= end {
+            return Err(MbufError::OffsetOutOfRange {
+                offset: payload,
+                size: end,
+            });
+        }
+
+        let block = payload - BLOCK_ALIGN;
+
+        if !block.is_multiple_of(BLOCK_ALIGN) {
+            return Err(MbufError::InvalidMetadata("offset is not a block payload"));
+        }
+
+        let size = pod_ref::<BlockHeader>(self.region.bytes(), block)?.size;
+
+        if size & FREE != 0 {
+            return Err(MbufError::InvalidMetadata("block is not allocated"));
+        }
+
+        let size = size as usize;
+
+        if size_class(size).is_none_or(|class| class_size(class) != size) || size > end - block {
+            return Err(MbufError::InvalidMetadata("corrupt block header"));
+        }
+
+        Ok((block, size))
+    }
+
+    pub fn header(&self) -> Result<&HeapHeader, MbufError> {
+        pod_ref(self.region.bytes(), 0)
+    }
+
+    pub(crate) fn header_mut(&mut self) -> Result<&mut HeapHeader, MbufError> {
+        pod_mut(self.region.bytes_mut(), 0)
+    }
+}
+
+/// Offset of the first block.
+pub(crate) const fn data_start() -> usize {
+    align_up(std::mem::size_of::<HeapHeader>(), BLOCK_ALIGN)
+}
+
+/// The smallest size class holding blocks of at least `size` bytes.
+pub(crate) fn size_class(size: usize) -> Option<usize> {
+    let size = size.max(1 << MIN_CLASS_SHIFT).checked_next_power_of_two()?;
+    let class = (size.trailing_zeros() - MIN_CLASS_SHIFT) as usize;
+
+    (class < SIZE_CLASSES).then_some(class)
+}
+
+pub(crate) const fn class_size(class: usize) -> usize {
+    1 << (class as u32 + MIN_CLASS_SHIFT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    #[test]
+    fn alloc_free_realloc() {
+        let mut heap = MbufHeap::new(AlignedBuffer::new()).unwrap();
+        let a = heap
+            .alloc_mbuf_from_slice::<u32, u64>(1, &[1, 2, 3])
+            .unwrap();
+        let b = heap.alloc_mbuf::<u8, u8>(2, 5).unwrap();
+        let end = heap.header().unwrap().end();
+
+        heap.free_mbuf(b).unwrap();
+        assert!(heap.free_mbuf(b).is_err());
+
+        let c = heap.alloc_mbuf::<u8, u8>(3, 4).unwrap();
+        assert_eq!(c.get(), b.get());
+        assert_eq!(heap.header().unwrap().end(), end);
+
+        assert_eq!(heap.realloc_mbuf(a, 2).unwrap(), a);
+        assert_eq!(&heap.get(a).unwrap()[..], &[1, 2]);
+        assert_eq!(heap.realloc_mbuf(a, 3).unwrap(), a);
+        assert_eq!(&heap.get(a).unwrap()[..], &[1, 2, 0]);
+
+        let moved = heap.realloc_mbuf(a, 100).unwrap();
+        assert_ne!(moved, a);
+        assert_eq!(&heap.get(moved).unwrap()[..4], &[1, 2, 0, 0]);
+        assert_eq!(*heap.get(moved).unwrap().get_metadata(), 1);
+        assert!(heap.free_mbuf(a).is_err());
+
+        heap.get_mut(moved).unwrap()[99] = 7;
+        assert_eq!(heap.get(moved).unwrap()[99], 7);
+    }
+
+    #[test]
+    fn reopen() {
+        let mut heap = MbufHeap::new(AlignedBuffer::new()).unwrap();
+        let a = heap
+            .alloc_mbuf_from_slice::<u32, u64>(1, &[1, 2, 3])
+            .unwrap();
+        let b = heap.alloc_mbuf_from_slice::<u32, u64>(2, &[4, 5]).unwrap();
+        heap.free_mbuf(a).unwrap();
+
+        let mut buffer = heap.into_inner();
+        let mut heap = MbufHeap::open(&mut buffer[..]).unwrap();
+        assert_eq!(&heap.get(b).unwrap()[..], &[4, 5]);
+        assert_eq!(heap.alloc_mbuf::<u32, u64>(0, 3).unwrap(), a);
+        assert!(heap.alloc_mbuf::<u8, u8>(0, 10000).is_err());
+    }
+
+    #[test]
+    fn open_rejects_garbage() {
+        assert!(MbufHeap::<_>::open(AlignedBuffer::zeroed(512)).is_err());
+    }
+
+    #[test]
+    fn corrupt_free_list() {
+        let mut heap = MbufHeap::new(AlignedBuffer::new()).unwrap();
+        let a = heap.alloc_mbuf::<u8, u8>(0, 5).unwrap();
+        heap.free_mbuf(a).unwrap();
+
+        let head = &mut heap.header_mut().unwrap().free;
+        let class = head.iter().position(|block| *block != 0).unwrap();
+        head[class] = 3;
+
+        assert!(matches!(
+            heap.alloc_mbuf::<u8, u8>(0, 5),
+            Err(MbufError::InvalidMetadata(_))
+        ));
+    }
+}
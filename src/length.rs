@@ -0,0 +1,29 @@
+use crate::Pod;
+
+/// Integer types an Mbuf can store its length as.
+/// # Safety
+/// `to_usize` must return the same value for equal inputs, and
+/// `from_usize(n)` followed by `to_usize` must return `Some(n)`.
+pub unsafe trait MbufLength: Pod {
+    fn from_usize(length: usize) -> Option<Self>;
+
+    fn to_usize(self) -> Option<usize>;
+}
+
+macro_rules! impl_native_length {
+    ($($t:ty),* $(,)?) => {
+        $(
+            unsafe impl MbufLength for $t {
+                fn from_usize(length: usize) -> Option<Self> {
+                    length.try_into().ok()
+                }
+
+                fn to_usize(self) -> Option<usize> {
+                    self.try_into().ok()
+                }
+            }
+        )*
+    };
+}
+
+impl_native_length!(u8, u16, u32, u64, usize);
@@ -1,11 +1,70 @@
+mod arena;
+mod boxed;
+mod buffer;
+#[cfg(feature = "checksum")]
+mod checksum;
+mod compact;
+mod endian;
+mod error;
+#[cfg(feature = "mmap")]
+mod file_region;
+mod growable;
+mod hash_map;
+mod header;
+mod heap;
+mod length;
+#[cfg(feature = "mmap")]
+mod mmap;
+mod nested;
+mod offset;
+mod pod;
+mod reader;
+mod region;
+mod sorted_index;
+mod string;
+mod string_table;
+#[cfg(all(test, feature = "mmap"))]
+mod temp_file;
+mod writer;
+
+pub use arena::{ArenaHeader, MbufArena};
+pub use boxed::MbufBox;
+pub use buffer::AlignedBuffer;
+#[cfg(feature = "checksum")]
+pub use checksum::Checksummed;
+pub use compact::{CompactionReport, Relocations};
+pub use endian::{
+    ByteSwap, F32Be, F32Le, F64Be, F64Le, I16Be, I16Le, I32Be, I32Le, I64Be, I64Le, U16Be, U16Le,
+    U32Be, U32Le, U64Be, U64Le,
+};
+pub use error::MbufError;
+#[cfg(feature = "mmap")]
+pub use file_region::FileRegion;
+pub use growable::{GrowableMbuf, GrowableMbufMut};
+pub use hash_map::{Bucket, HashMapHeader, MbufHashMap, MbufHashMapMut};
+pub use header::{Endianness, RegionHeader};
+pub use heap::{HeapHeader, MbufHeap};
+pub use length::MbufLength;
+#[cfg(feature = "mmap")]
+pub use mmap::{MappedMbuf, MappedMbufMut};
+pub use nested::NestedMbuf;
+pub use offset::MbufOffset;
+pub use pod::Pod;
+pub use reader::MbufReader;
+pub use region::Region;
+pub use sorted_index::{IndexEntry, MbufSortedIndex, SortedIndexIter};
+pub use string::{MbufCStr, MbufStr};
+pub use string_table::{StringId, StringTable, StringTableWriter};
+pub use writer::MbufWriter;
+
 #[repr(C)]
-pub struct Mbuf<'lt, M, D> {
+pub struct Mbuf<'lt, M, D, L = usize> {
     metadata: M,
-    length: usize,
+    length: L,
     _marker: std::marker::PhantomData<&'lt D>,
 }
 
-impl<'lt, M, D> Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, D, L> {
     pub fn to_slice(&self) -> &[D] {
         self
     }
@@ -28,64 +87,313 @@ impl<'lt, M, D> Mbuf<'lt, M, D> {
 
     pub fn len(&self) -> usize {
         self.length
+            .to_usize()
+            .expect("Mbuf length exceeds the address space")
+    }
+
+    /// Address of the first element, following `length` and alignment padding.
+    fn data_pointer(&self) -> *const D {
+        align::<D>(&self.length as *const L as usize + std::mem::size_of::<L>())
+    }
+
+    /// Stores `length`, panicking if it is not representable by `L`.
+    fn set_len(&mut self, length: usize) {
+        self.length = L::from_usize(length).expect("Mbuf length exceeds the range of L");
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// Number of bytes occupied by `metadata` and `length`, including padding between them.
+    pub const fn header_size() -> usize {
+        std::mem::offset_of!(Self, length) + std::mem::size_of::<L>()
+    }
+
+    /// Byte offset of the first element, provided the Mbuf is aligned to [`Self::required_align`].
+    pub const fn data_offset() -> usize {
+        align_up(Self::header_size(), std::mem::align_of::<D>())
+    }
+
+    /// Alignment an Mbuf<'lt, M, D, L> must be placed at for its layout to match [`Self::data_offset`].
+    pub const fn required_align() -> usize {
+        let header = std::mem::align_of::<Self>();
+        let data = std::mem::align_of::<D>();
+
+        if header > data {
+            header
+        } else {
+            data
+        }
+    }
+
+    /// Number of bytes an Mbuf<'lt, M, D, L> with `length` elements spans.
+    pub const fn total_size(length: usize) -> Result<usize, MbufError> {
+        let header = std::mem::size_of::<Self>();
+
+        let Some(data) = length.checked_mul(std::mem::size_of::<D>()) else {
+            return Err(MbufError::LengthOverflow { length });
+        };
+
+        let Some(size) = data.checked_add(Self::data_offset()) else {
+            return Err(MbufError::LengthOverflow { length });
+        };
+
+        if size > header {
+            Ok(size)
+        } else {
+            Ok(header)
+        }
+    }
+
+    /// Size and alignment of an Mbuf<'lt, M, D, L> with `length` elements, e.g. for [`std::alloc::alloc`].
+    pub const fn layout(length: usize) -> Result<std::alloc::Layout, MbufError> {
+        let size = match Self::total_size(length) {
+            Ok(size) => size,
+            Err(err) => return Err(err),
+        };
+
+        match std::alloc::Layout::from_size_align(size, Self::required_align()) {
+            Ok(layout) => Ok(layout),
+            Err(_) => Err(MbufError::LengthOverflow { length }),
+        }
     }
 }
 
-impl<'lt, M, D> Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, D, L> {
     /// Declares an Mbuf begins at a given pointer
     /// # Safety
-    /// Safe only if the pointer points to a valid Mbuf<'lt, M, D>.
+    /// Safe only if the pointer points to a valid Mbuf<'lt, M, D, L>.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     pub unsafe fn at_ptr(pointer: *const u8) -> &'lt Self {
-        &*(pointer as *const Mbuf<'lt, M, D>)
+        &*(pointer as *const Mbuf<'lt, M, D, L>)
     }
 
     /// Declares a mutable Mbuf begins at a given pointer
     /// # Safety
-    /// Safe only if the pointer points to a valid Mbuf<'lt, M, D> in writable memory.
+    /// Safe only if the pointer points to a valid Mbuf<'lt, M, D, L> in writable memory.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     pub unsafe fn at_ptr_mut(pointer: *mut u8) -> &'lt mut Self {
-        &mut *(pointer as *mut Mbuf<'lt, M, D>)
+        &mut *(pointer as *mut Mbuf<'lt, M, D, L>)
     }
 
     /// Declares an Mbuf begins at a given byte offset from a given pointer
     /// # Safety
-    /// Safe only if the the region at (pointer + offset) contains a valid Mbuf<'lt, M, D>.
+    /// Safe only if the the region at (pointer + offset) contains a valid Mbuf<'lt, M, D, L>.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     pub unsafe fn at_offset(pointer: *const u8, offset: usize) -> &'lt Self {
-        &*((pointer.add(offset)) as *const Mbuf<'lt, M, D>)
+        &*((pointer.add(offset)) as *const Mbuf<'lt, M, D, L>)
     }
 
     /// Declares a mutable Mbuf begins at a given byte offset from a given pointer
     /// # Safety
-    /// Safe only if the the region at (pointer + offset) contains a valid Mbuf<'lt, M, D> in writable memory.
+    /// Safe only if the the region at (pointer + offset) contains a valid Mbuf<'lt, M, D, L> in writable memory.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     pub unsafe fn at_offset_mut(pointer: *mut u8, offset: usize) -> &'lt mut Self {
-        &mut *((pointer.add(offset)) as *mut Mbuf<'lt, M, D>)
+        &mut *((pointer.add(offset)) as *mut Mbuf<'lt, M, D, L>)
     }
 
     /// declares a memory buffer **without data initialization -- items must be initialized by caller**
     /// # Safety
-    /// Safe if the memory region pointed to is large enough to hold an Mbuf<'lt, M, D> and is writable.
+    /// Safe if the memory region pointed to is large enough to hold an Mbuf<'lt, M, D, L> and is writable.
     /// <br>**Calling this function does not initialize data values in the Mbuf.**
     /// <br>**Dropping uninitialized data values may be undefined behaviour.**
-    /// <br>Don't do this unless you know what you're doing.
+    /// <br>Don't do this unless you know what you're doing; prefer [`Mbuf::init_uninit`].
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     pub unsafe fn init_at_ptr(pointer: *mut u8, metadata: M, length: usize) -> &'lt mut Self {
-        let mbuf = Mbuf::at_ptr_mut(pointer);
+        let mbuf = pointer as *mut Mbuf<'lt, M, D, L>;
 
-        mbuf.metadata = metadata;
-        mbuf.length = length;
+        // the region may hold garbage, which must not be dropped
+        std::ptr::addr_of_mut!((*mbuf).metadata).write(metadata);
+        std::ptr::addr_of_mut!((*mbuf).length)
+            .write(L::from_usize(length).expect("Mbuf length exceeds the range of L"));
 
-        mbuf
+        &mut *mbuf
+    }
+
+    /// Declares an Mbuf of `length` uninitialized elements at `pointer`.
+    /// <br>Initialize every element, then call [`Mbuf::assume_init`].
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `length` elements.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn init_uninit(
+        pointer: *mut u8,
+        metadata: M,
+        length: usize,
+    ) -> &'lt mut Mbuf<'lt, M, std::mem::MaybeUninit<D>, L> {
+        Mbuf::init_at_ptr(pointer, metadata, length)
+    }
+
+    /// Drops the metadata and all elements of this Mbuf without releasing its memory.
+    /// # Safety
+    /// - The metadata and all elements must be initialized.
+    /// - The Mbuf must not be used afterwards, except to be initialized again.
+    pub unsafe fn drop_in_place(&mut self) {
+        std::ptr::drop_in_place(self.to_slice_mut() as *mut [D]);
+        std::ptr::drop_in_place(&mut self.metadata as *mut M);
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, std::mem::MaybeUninit<D>, L> {
+    /// Reinterprets this Mbuf as holding initialized elements.
+    /// # Safety
+    /// All elements must have been initialized.
+    pub unsafe fn assume_init(&mut self) -> &mut Mbuf<'lt, M, D, L> {
+        &mut *(self as *mut Self as *mut Mbuf<'lt, M, D, L>)
+    }
+
+    /// Reinterprets this Mbuf as holding initialized elements.
+    /// # Safety
+    /// All elements must have been initialized.
+    pub unsafe fn assume_init_ref(&self) -> &Mbuf<'lt, M, D, L> {
+        &*(self as *const Self as *const Mbuf<'lt, M, D, L>)
     }
 }
 
-impl<'lt, M, D: Copy> Mbuf<'lt, M, D> {
+impl<'lt, M: Pod, D: Pod, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// Interprets the beginning of `bytes` as an Mbuf, checking alignment and bounds.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::from_bytes_at(bytes, 0)
+    }
+
+    /// Interprets the beginning of `bytes` as a mutable Mbuf, checking alignment and bounds.
+    pub fn from_bytes_mut(bytes: &'lt mut [u8]) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        Self::from_bytes_at_mut(bytes, 0)
+    }
+
+    /// Interprets the bytes at `offset` in `region` as an Mbuf, checking alignment and bounds.
+    pub fn from_bytes_at(region: &'lt [u8], offset: usize) -> Result<&'lt Self, MbufError> {
+        unsafe { Self::from_bytes_at_unchecked(region, offset) }
+    }
+
+    /// Interprets the bytes at `offset` in `region` as a mutable Mbuf, checking alignment and bounds.
+    pub fn from_bytes_at_mut(
+        region: &'lt mut [u8],
+        offset: usize,
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        unsafe { Self::from_bytes_at_mut_unchecked(region, offset) }
+    }
+
+    /// Copies `metadata` and `data` into the beginning of `bytes`, checking alignment and bounds.
+    pub fn write_to_bytes(
+        bytes: &'lt mut [u8],
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        Self::check_fits(bytes.as_ptr(), bytes.len(), data.len())?;
+
+        Ok(MbufMut {
+            mbuf: unsafe { Self::write_to_ptr_mut(bytes.as_mut_ptr(), metadata, data) },
+        })
+    }
+
+    /// Copies `metadata` and `data` to `offset` in `region`, checking alignment and bounds.
+    pub fn write_to_bytes_at(
+        region: &'lt mut [u8],
+        offset: usize,
+        metadata: M,
+        data: &[D],
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        let size = region.len();
+        let bytes = region
+            .get_mut(offset..)
+            .ok_or(MbufError::OffsetOutOfRange { offset, size })?;
+
+        Self::write_to_bytes(bytes, metadata, data)
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// [`Mbuf::from_bytes_at`] without the [`Pod`] bounds, for types only known to be plain data
+    /// by their callers, e.g. generic wrappers whose padding is checked at compile time.
+    /// # Safety
+    /// `M` and `D` must be valid for every bit pattern.
+    unsafe fn from_bytes_at_unchecked(
+        region: &'lt [u8],
+        offset: usize,
+    ) -> Result<&'lt Self, MbufError> {
+        let bytes = region.get(offset..).ok_or(MbufError::OffsetOutOfRange {
+            offset,
+            size: region.len(),
+        })?;
+
+        Self::validate(bytes.as_ptr(), bytes.len())?;
+
+        Ok(Self::at_ptr(bytes.as_ptr()))
+    }
+
+    /// [`Mbuf::from_bytes_at_mut`] without the [`Pod`] bounds.
+    /// # Safety
+    /// `M` and `D` must be valid for every bit pattern, and must not contain padding.
+    unsafe fn from_bytes_at_mut_unchecked(
+        region: &'lt mut [u8],
+        offset: usize,
+    ) -> Result<MbufMut<'lt, M, D, L>, MbufError> {
+        let size = region.len();
+        let bytes = region
+            .get_mut(offset..)
+            .ok_or(MbufError::OffsetOutOfRange { offset, size })?;
+
+        Self::validate(bytes.as_ptr(), bytes.len())?;
+
+        Ok(MbufMut {
+            mbuf: Self::at_ptr_mut(bytes.as_mut_ptr()),
+        })
+    }
+
+    /// Checks that `available` bytes at `pointer` hold a complete Mbuf<'lt, M, D, L>.
+    fn validate(pointer: *const u8, available: usize) -> Result<(), MbufError> {
+        Self::check_fits(pointer, available, 0)?;
+
+        let length = unsafe { (*(pointer as *const Self)).length };
+        let length = length
+            .to_usize()
+            .ok_or(MbufError::LengthOverflow { length: usize::MAX })?;
+
+        Self::check_fits(pointer, available, length)
+    }
+
+    /// Checks that an Mbuf<'lt, M, D, L> of `length` elements fits into `available` bytes at `pointer`.
+    fn check_fits(pointer: *const u8, available: usize, length: usize) -> Result<(), MbufError> {
+        let address = pointer as usize;
+        let alignment = std::mem::align_of::<Self>();
+
+        if !address.is_multiple_of(alignment) {
+            return Err(MbufError::MisalignedPointer {
+                address,
+                align: alignment,
+            });
+        }
+
+        if L::from_usize(length).is_none() {
+            return Err(MbufError::LengthOverflow { length });
+        }
+
+        let header = std::mem::size_of::<Self>();
+        let data = align::<D>(address + Self::header_size()) as usize - address;
+
+        let required = length
+            .checked_mul(std::mem::size_of::<D>())
+            .and_then(|size| size.checked_add(data))
+            .ok_or(MbufError::LengthOverflow { length })?
+            .max(header);
+
+        if available < required {
+            return Err(MbufError::RegionTooSmall {
+                required,
+                available,
+            });
+        }
+
+        Ok(())
+    }
+}
+
+impl<'lt, M, D: Copy, L: MbufLength> Mbuf<'lt, M, D, L> {
     /// Declares an Mbuf at `pointer` and copies `metadata` and `data` into it.
     /// # Safety
-    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `usize` + `data`.
-    /// - `pointer` must be aligned to at least `usize` and at least `M`.
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `data`.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     /// - The memory region at `pointer` must be writable
     pub unsafe fn write_to_ptr(pointer: *mut u8, metadata: M, data: &[D]) -> &'lt Self {
@@ -94,8 +402,8 @@ impl<'lt, M, D: Copy> Mbuf<'lt, M, D> {
 
     /// Declares a mutable Mbuf at `pointer` and copies `metadata` and `data` into it.
     /// # Safety
-    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `usize` + `data`.
-    /// - `pointer` must be aligned to at least `usize` and at least `M`.
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `data`.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     /// - The memory region at `pointer` must be writable
     pub unsafe fn write_to_ptr_mut(pointer: *mut u8, metadata: M, data: &[D]) -> &'lt mut Self {
@@ -108,8 +416,8 @@ impl<'lt, M, D: Copy> Mbuf<'lt, M, D> {
 
     /// Declares an Mbuf at `pointer + offset` and copies `metadata` and `data` into it.
     /// # Safety
-    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `usize` + `data`.
-    /// - `pointer` must be aligned to at least `usize` and at least `M`.
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `data`.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     /// - The memory region at `pointer` must be writable
     pub unsafe fn write_to_offset(
@@ -123,8 +431,8 @@ impl<'lt, M, D: Copy> Mbuf<'lt, M, D> {
 
     /// Declares a mutable Mbuf at `pointer + offset` and copies `metadata` and `data` into it.
     /// # Safety
-    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `usize` + `data`.
-    /// - `pointer` must be aligned to at least `usize` and at least `M`.
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `data`.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
     /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
     /// - The memory region at `pointer` must be writable
     pub unsafe fn write_to_offset_mut(
@@ -137,47 +445,296 @@ impl<'lt, M, D: Copy> Mbuf<'lt, M, D> {
     }
 }
 
-impl<'lt, M, D> AsRef<[D]> for Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> Mbuf<'lt, M, D, L> {
+    /// Declares an Mbuf at `pointer` and moves `metadata` and the items of `iter` into it.
+    /// <br>At most `iter.len()` items are taken; if `iter` yields fewer, the Mbuf is shorter.
+    /// <br>If `iter` panics, the items written so far and `metadata` are dropped.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `iter.len()` elements.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_from_iter(
+        pointer: *mut u8,
+        metadata: M,
+        iter: impl ExactSizeIterator<Item = D>,
+    ) -> &'lt Self {
+        Mbuf::write_from_iter_mut(pointer, metadata, iter)
+    }
+
+    /// Declares a mutable Mbuf at `pointer` and moves `metadata` and the items of `iter` into it.
+    /// <br>At most `iter.len()` items are taken; if `iter` yields fewer, the Mbuf is shorter.
+    /// <br>If `iter` panics, the items written so far and `metadata` are dropped.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `iter.len()` elements.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_from_iter_mut(
+        pointer: *mut u8,
+        metadata: M,
+        iter: impl ExactSizeIterator<Item = D>,
+    ) -> &'lt mut Self {
+        let capacity = iter.len();
+        let mbuf = Mbuf::<M, D, L>::init_at_ptr(pointer, metadata, 0);
+        let data = align::<D>(pointer as usize + Self::header_size()) as *mut D;
+        let guard = DropGuard(mbuf);
+
+        for (index, item) in iter.take(capacity).enumerate() {
+            data.add(index).write(item);
+            guard.0.set_len(index + 1);
+        }
+
+        std::mem::forget(guard);
+
+        Mbuf::at_ptr_mut(pointer)
+    }
+
+    /// Declares an Mbuf at `pointer` holding `metadata` and `length` elements produced by `f(index)`.
+    /// <br>If `f` panics, the elements written so far and `metadata` are dropped.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `length` elements.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_with(
+        pointer: *mut u8,
+        metadata: M,
+        length: usize,
+        f: impl FnMut(usize) -> D,
+    ) -> &'lt Self {
+        Mbuf::write_with_mut(pointer, metadata, length, f)
+    }
+
+    /// Declares a mutable Mbuf at `pointer` holding `metadata` and `length` elements produced by `f(index)`.
+    /// <br>If `f` panics, the elements written so far and `metadata` are dropped.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `length` elements.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&Mbuf`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_with_mut(
+        pointer: *mut u8,
+        metadata: M,
+        length: usize,
+        f: impl FnMut(usize) -> D,
+    ) -> &'lt mut Self {
+        Mbuf::write_from_iter_mut(pointer, metadata, (0..length).map(f))
+    }
+}
+
+/// Drops a partially written Mbuf if construction unwinds.
+struct DropGuard<'a, 'lt, M, D, L: MbufLength>(&'a mut Mbuf<'lt, M, D, L>);
+
+impl<M, D, L: MbufLength> Drop for DropGuard<'_, '_, M, D, L> {
+    fn drop(&mut self) {
+        unsafe { self.0.drop_in_place() }
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> AsRef<[D]> for Mbuf<'lt, M, D, L> {
     fn as_ref(&self) -> &[D] {
         self
     }
 }
 
-impl<'lt, M, D> AsMut<[D]> for Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> AsMut<[D]> for Mbuf<'lt, M, D, L> {
     fn as_mut(&mut self) -> &mut [D] {
         self
     }
 }
 
-impl<'lt, M, D> std::ops::Deref for Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> std::ops::Deref for Mbuf<'lt, M, D, L> {
     type Target = [D];
 
     fn deref(&self) -> &Self::Target {
-        unsafe {
-            let address = &self.length as *const usize as usize + std::mem::size_of::<usize>();
-
-            std::slice::from_raw_parts(align::<D>(address), self.length)
-        }
+        unsafe { std::slice::from_raw_parts(self.data_pointer(), self.len()) }
     }
 }
 
-impl<'lt, M, D> std::ops::DerefMut for Mbuf<'lt, M, D> {
+impl<'lt, M, D, L: MbufLength> std::ops::DerefMut for Mbuf<'lt, M, D, L> {
     fn deref_mut(&mut self) -> &mut Self::Target {
-        unsafe {
-            let address = &self.length as *const usize as usize + std::mem::size_of::<usize>();
+        unsafe { std::slice::from_raw_parts_mut(self.data_pointer() as *mut D, self.len()) }
+    }
+}
 
-            std::slice::from_raw_parts_mut(align::<D>(address) as *mut D, self.length)
-        }
+/// Mutable access to the metadata and elements of an Mbuf<M, D, L>, as returned by safe constructors.
+/// <br>Unlike `&mut Mbuf`, it cannot be used to swap or overwrite the Mbuf itself,
+/// whose length must keep describing the memory following it.
+/// <br>Dereferences to the elements; the Mbuf itself is available through [`MbufMut::as_mbuf`].
+pub struct MbufMut<'lt, M, D, L = usize> {
+    mbuf: &'lt mut Mbuf<'lt, M, D, L>,
+}
+
+impl<'lt, M, D, L: MbufLength> MbufMut<'lt, M, D, L> {
+    pub fn as_mbuf(&self) -> &Mbuf<'lt, M, D, L> {
+        self.mbuf
+    }
+
+    pub fn get_metadata(&self) -> &M {
+        self.mbuf.get_metadata()
+    }
+
+    pub fn set_metadata(&mut self, metadata: M) -> M {
+        self.mbuf.set_metadata(metadata)
+    }
+
+    /// Converts this handle into a slice of the elements, borrowed for as long as the Mbuf.
+    pub fn into_slice(self) -> &'lt mut [D] {
+        self.mbuf.to_slice_mut()
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> std::ops::Deref for MbufMut<'lt, M, D, L> {
+    type Target = [D];
+
+    fn deref(&self) -> &Self::Target {
+        self.mbuf
+    }
+}
+
+impl<'lt, M, D, L: MbufLength> std::ops::DerefMut for MbufMut<'lt, M, D, L> {
+    fn deref_mut(&mut self) -> &mut Self::Target {
+        self.mbuf
     }
 }
 
 const fn align<T>(address: usize) -> *const T {
-    let align_size = std::mem::align_of::<T>();
+    align_up(address, std::mem::align_of::<T>()) as *const T
+}
+
+const fn align_up(address: usize, align_size: usize) -> usize {
     let remainder = address % align_size;
 
     if remainder == 0 {
-        address as *const T
+        address
     } else {
-        (address + align_size - remainder) as *const T
+        address + align_size - remainder
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::rc::Rc;
+
+    use super::*;
+
+    #[test]
+    fn from_bytes_round_trip() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let bytes = &mut buffer[..];
+
+        Mbuf::<u32, u16>::write_to_bytes(bytes, 7, &[1, 2, 3]).unwrap();
+
+        let mbuf = Mbuf::<u32, u16>::from_bytes(bytes).unwrap();
+        assert_eq!(*mbuf.get_metadata(), 7);
+        assert_eq!(&**mbuf, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn from_bytes_misaligned() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let bytes = &mut buffer[..];
+
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes(&bytes[1..]),
+            Err(MbufError::MisalignedPointer { .. })
+        ));
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes_at(bytes, 4),
+            Err(MbufError::MisalignedPointer { .. })
+        ));
+    }
+
+    #[test]
+    fn from_bytes_too_small() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let bytes = &mut buffer[..];
+
+        Mbuf::<u32, u16>::write_to_bytes(bytes, 7, &[1, 2, 3]).unwrap();
+
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes(&bytes[..4]),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes(&bytes[..21]),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+        assert!(Mbuf::<u32, u16>::from_bytes(&bytes[..22]).is_ok());
+        assert!(matches!(
+            Mbuf::<u32, u16>::write_to_bytes(&mut bytes[..21], 7, &[1, 2, 3]),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+    }
+
+    #[test]
+    fn from_bytes_offset_out_of_range() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let bytes = &mut buffer[..];
+
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes_at(bytes, 72),
+            Err(MbufError::OffsetOutOfRange { .. })
+        ));
+        assert!(matches!(
+            Mbuf::<u32, u16>::write_to_bytes_at(bytes, 72, 7, &[1]),
+            Err(MbufError::OffsetOutOfRange { .. })
+        ));
+    }
+
+    #[test]
+    fn from_bytes_length_overflow() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let bytes = &mut buffer[..];
+
+        bytes[8..16].copy_from_slice(&usize::MAX.to_ne_bytes());
+
+        assert!(matches!(
+            Mbuf::<u32, u16>::from_bytes(bytes),
+            Err(MbufError::LengthOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn write_from_iter_drops_on_panic() {
+        let mut buffer = AlignedBuffer::zeroed(128);
+        let pointer = buffer.as_mut_ptr();
+        let rc = Rc::new(());
+
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| unsafe {
+            Mbuf::<Rc<()>, Rc<()>>::write_from_iter(
+                pointer,
+                rc.clone(),
+                (0..4).map(|index| {
+                    assert_ne!(index, 2);
+                    rc.clone()
+                }),
+            );
+        }));
+
+        assert!(result.is_err());
+        assert_eq!(Rc::strong_count(&rc), 1);
+    }
+
+    #[test]
+    fn write_from_iter_and_drop_in_place() {
+        let mut buffer = AlignedBuffer::zeroed(128);
+        let pointer = buffer.as_mut_ptr();
+        let rc = Rc::new(());
+
+        unsafe {
+            let mbuf = Mbuf::<Rc<()>, String>::write_with(pointer, rc.clone(), 4, |index| {
+                index.to_string()
+            });
+            assert_eq!(&**mbuf, &["0", "1", "2", "3"]);
+            assert_eq!(Rc::strong_count(&rc), 2);
+
+            Mbuf::<Rc<()>, String>::at_ptr_mut(pointer).drop_in_place();
+            assert_eq!(Rc::strong_count(&rc), 1);
+
+            let mbuf = Mbuf::<u8, u32>::write_from_iter(pointer, 1, [1u32, 2, 3].into_iter());
+            assert_eq!(&**mbuf, &[1, 2, 3]);
+        }
     }
 }
@@ -0,0 +1,352 @@
+use std::fs::{File, OpenOptions};
+use std::marker::PhantomData;
+use std::path::Path;
+
+use memmap2::{Mmap, MmapMut};
+
+use crate::{Mbuf, MbufError, MbufLength, MbufMut, Pod, Region, RegionHeader};
+
+/// A read-only memory map holding a validated Mbuf<M, D, L>.
+pub struct MappedMbuf<M, D, L = usize> {
+    map: Mmap,
+    offset: usize,
+    _marker: PhantomData<(M, D, L)>,
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> MappedMbuf<M, D, L> {
+    /// Maps the file at `path` read-only and validates the Mbuf at its beginning.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Self::open_at(path, 0)
+    }
+
+    /// Maps the file at `path` read-only and validates the Mbuf at byte `offset`.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open_at(path: impl AsRef<Path>, offset: usize) -> std::io::Result<Self> {
+        let map = Mmap::map(&File::open(path)?)?;
+
+        Ok(Self::from_mmap(map, offset)?)
+    }
+
+    /// Maps the file at `path` read-only, verifies its [`RegionHeader`] and validates the Mbuf following it.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open_with_header(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let map = Mmap::map(&File::open(path)?)?;
+
+        RegionHeader::read::<M, D, L>(&map)?;
+
+        Ok(Self::from_mmap(
+            map,
+            RegionHeader::data_offset::<M, D, L>(),
+        )?)
+    }
+
+    /// Validates the Mbuf at byte `offset` of an existing map.
+    pub fn from_mmap(map: Mmap, offset: usize) -> Result<Self, MbufError> {
+        Mbuf::<M, D, L>::from_bytes_at(&map, offset)?;
+
+        Ok(Self {
+            map,
+            offset,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Byte offset of the Mbuf within the map.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    pub fn into_inner(self) -> Mmap {
+        self.map
+    }
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> std::ops::Deref for MappedMbuf<M, D, L> {
+    type Target = Mbuf<'static, M, D, L>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { Mbuf::at_offset(self.map.as_ptr(), self.offset) }
+    }
+}
+
+/// A writable memory map holding a validated Mbuf<M, D, L>.
+pub struct MappedMbufMut<M, D, L = usize> {
+    map: MmapMut,
+    offset: usize,
+    _marker: PhantomData<(M, D, L)>,
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> MappedMbufMut<M, D, L> {
+    /// Maps the file at `path` read-write and validates the Mbuf at its beginning.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        Self::open_at(path, 0)
+    }
+
+    /// Maps the file at `path` read-write and validates the Mbuf at byte `offset`.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open_at(path: impl AsRef<Path>, offset: usize) -> std::io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let map = MmapMut::map_mut(&file)?;
+
+        Ok(Self::from_mmap(map, offset)?)
+    }
+
+    /// Maps the file at `path` read-write, verifies its [`RegionHeader`] and validates the Mbuf following it.
+    /// # Safety
+    /// The file must not be modified or truncated by anyone else while it is mapped.
+    pub unsafe fn open_with_header(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().read(true).write(true).open(path)?;
+        let map = MmapMut::map_mut(&file)?;
+
+        RegionHeader::read::<M, D, L>(&map)?;
+
+        Ok(Self::from_mmap(
+            map,
+            RegionHeader::data_offset::<M, D, L>(),
+        )?)
+    }
+
+    /// Validates the Mbuf at byte `offset` of an existing map.
+    pub fn from_mmap(mut map: MmapMut, offset: usize) -> Result<Self, MbufError> {
+        Mbuf::<M, D, L>::from_bytes_at_mut(&mut map, offset)?;
+
+        Ok(Self {
+            map,
+            offset,
+            _marker: PhantomData,
+        })
+    }
+
+    /// Byte offset of the Mbuf within the map.
+    pub fn offset(&self) -> usize {
+        self.offset
+    }
+
+    /// Mutable access to the metadata and elements of the Mbuf.
+    pub fn get_mut(&mut self) -> MbufMut<'_, M, D, L> {
+        MbufMut {
+            mbuf: unsafe { Mbuf::at_offset_mut(self.map.as_mut_ptr(), self.offset) },
+        }
+    }
+
+    pub fn into_inner(self) -> MmapMut {
+        self.map
+    }
+
+    /// Writes all modified pages of the map to the file, blocking until done.
+    pub fn flush(&self) -> std::io::Result<()> {
+        self.map.flush()
+    }
+
+    /// Starts writing all modified pages of the map to the file, without waiting.
+    pub fn flush_async(&self) -> std::io::Result<()> {
+        self.map.flush_async()
+    }
+
+    /// Writes the pages covered by the metadata, length and data of `mbuf` to the file, blocking until done.
+    /// <br>`mbuf` must lie within this map, e.g. be this Mbuf or be resolved from it.
+    pub fn flush_range<M2, D2, L2: MbufLength>(
+        &self,
+        mbuf: &Mbuf<'_, M2, D2, L2>,
+    ) -> std::io::Result<()> {
+        flush_mbuf(&self.map, mbuf, false)
+    }
+
+    /// Starts writing the pages covered by the metadata, length and data of `mbuf` to the file, without waiting.
+    pub fn flush_async_range<M2, D2, L2: MbufLength>(
+        &self,
+        mbuf: &Mbuf<'_, M2, D2, L2>,
+    ) -> std::io::Result<()> {
+        flush_mbuf(&self.map, mbuf, true)
+    }
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> std::ops::Deref for MappedMbufMut<M, D, L> {
+    type Target = Mbuf<'static, M, D, L>;
+
+    fn deref(&self) -> &Self::Target {
+        unsafe { Mbuf::at_offset(self.map.as_ptr(), self.offset) }
+    }
+}
+
+impl Region for MmapMut {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
+
+/// Flushes the pages of `map` covered by `mbuf`; the map rounds the range out to whole pages.
+pub(crate) fn flush_mbuf<M, D, L: MbufLength>(
+    map: &MmapMut,
+    mbuf: &Mbuf<'_, M, D, L>,
+    asynchronous: bool,
+) -> std::io::Result<()> {
+    let range = range_in(mbuf, map)?;
+
+    if asynchronous {
+        map.flush_async_range(range.start, range.len())
+    } else {
+        map.flush_range(range.start, range.len())
+    }
+}
+
+/// Range of bytes of `region` covered by the metadata, length and data of `mbuf`.
+fn range_in<M, D, L: MbufLength>(
+    mbuf: &Mbuf<'_, M, D, L>,
+    region: &[u8],
+) -> Result<std::ops::Range<usize>, MbufError> {
+    let base = region.as_ptr() as usize;
+    let start = mbuf as *const Mbuf<'_, M, D, L> as usize;
+    let overflow = MbufError::LengthOverflow { length: mbuf.len() };
+    let end = mbuf
+        .len()
+        .checked_mul(std::mem::size_of::<D>())
+        .and_then(|size| (mbuf.data_pointer() as usize).checked_add(size))
+        .ok_or(overflow)?
+        .max(start + std::mem::size_of::<Mbuf<'_, M, D, L>>());
+
+    if start < base || end > base + region.len() {
+        return Err(MbufError::OffsetOutOfRange {
+            offset: start.wrapping_sub(base),
+            size: region.len(),
+        });
+    }
+
+    Ok(start - base..end - base)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::temp_file::TempFile;
+    use crate::AlignedBuffer;
+
+    /// Writes an Mbuf<u32, u16> holding 7 and [1, 2, 3] at byte `offset` of a new file.
+    fn write_file(offset: usize, header: bool) -> TempFile {
+        let temp = TempFile::new();
+        let mut buffer = AlignedBuffer::zeroed(offset + 32);
+
+        if header {
+            Mbuf::<u32, u16>::write_to_bytes_with_header(&mut buffer, 7, &[1, 2, 3]).unwrap();
+        } else {
+            Mbuf::<u32, u16>::write_to_bytes_at(&mut buffer, offset, 7, &[1, 2, 3]).unwrap();
+        }
+
+        std::fs::write(temp.path(), &buffer[..offset + 22]).unwrap();
+
+        temp
+    }
+
+    #[test]
+    fn open() {
+        let temp = write_file(0, false);
+        let mapped = unsafe { MappedMbuf::<u32, u16>::open(temp.path()) }.unwrap();
+
+        assert_eq!(mapped.offset(), 0);
+        assert_eq!(*mapped.get_metadata(), 7);
+        assert_eq!(&**mapped, &[1, 2, 3]);
+
+        let map = unsafe { Mmap::map(&File::open(temp.path()).unwrap()) }.unwrap();
+        let mapped = MappedMbuf::<u32, u16>::from_mmap(map, 0).unwrap();
+        assert_eq!(&**mapped, &[1, 2, 3]);
+    }
+
+    #[test]
+    fn open_at_and_with_header() {
+        let temp = write_file(32, false);
+        let mapped = unsafe { MappedMbuf::<u32, u16>::open_at(temp.path(), 32) }.unwrap();
+        assert_eq!(&**mapped, &[1, 2, 3]);
+
+        let temp = write_file(RegionHeader::data_offset::<u32, u16, usize>(), true);
+        let mapped = unsafe { MappedMbuf::<u32, u16>::open_with_header(temp.path()) }.unwrap();
+        assert_eq!(*mapped.get_metadata(), 7);
+
+        let mapped = unsafe { MappedMbufMut::<u32, u16>::open_with_header(temp.path()) }.unwrap();
+        assert_eq!(&**mapped, &[1, 2, 3]);
+
+        let err = unsafe { MappedMbuf::<u64, u16>::open_with_header(temp.path()) }.err();
+        assert_eq!(err.unwrap().kind(), std::io::ErrorKind::InvalidData);
+    }
+
+    #[test]
+    fn writes_through() {
+        let temp = write_file(32, false);
+        let mut mapped = unsafe { MappedMbufMut::<u32, u16>::open_at(temp.path(), 32) }.unwrap();
+
+        mapped.get_mut().set_metadata(8);
+        mapped.get_mut()[2] = 4;
+        mapped.flush_range(&mapped).unwrap();
+        drop(mapped);
+
+        let mapped = unsafe { MappedMbuf::<u32, u16>::open_at(temp.path(), 32) }.unwrap();
+        assert_eq!(*mapped.get_metadata(), 8);
+        assert_eq!(&**mapped, &[1, 2, 4]);
+
+        let file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .open(temp.path())
+            .unwrap();
+        let map = unsafe { MmapMut::map_mut(&file) }.unwrap();
+        let mapped = MappedMbufMut::<u32, u16>::from_mmap(map, 32).unwrap();
+        assert_eq!(mapped.into_inner().len(), 54);
+    }
+
+    #[test]
+    fn rejects_truncated_file() {
+        let temp = write_file(0, false);
+        let file = OpenOptions::new().write(true).open(temp.path()).unwrap();
+        file.set_len(21).unwrap();
+
+        let err = unsafe { MappedMbuf::<u32, u16>::open(temp.path()) }.err();
+        assert_eq!(err.unwrap().kind(), std::io::ErrorKind::InvalidData);
+
+        let err = unsafe { MappedMbufMut::<u32, u16>::open(temp.path()) }.err();
+        assert_eq!(err.unwrap().kind(), std::io::ErrorKind::InvalidData);
+
+        let map = unsafe { Mmap::map(&File::open(temp.path()).unwrap()) }.unwrap();
+        assert_eq!(
+            MappedMbuf::<u32, u16>::from_mmap(map, 0).err(),
+            Some(MbufError::RegionTooSmall {
+                required: 22,
+                available: 21
+            })
+        );
+    }
+
+    #[test]
+    fn range_in_region() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        Mbuf::<u32, u16>::write_to_bytes_at(&mut buffer, 16, 1, &[1, 2, 3]).unwrap();
+        Mbuf::<u32, u16>::write_to_bytes_at(&mut buffer, 48, 2, &[]).unwrap();
+        let other = AlignedBuffer::zeroed(64);
+
+        let mbuf = Mbuf::<u32, u16>::from_bytes_at(&buffer, 16).unwrap();
+        let empty = Mbuf::<u32, u16>::from_bytes_at(&buffer, 48).unwrap();
+
+        assert_eq!(range_in(mbuf, &buffer), Ok(16..38));
+        assert_eq!(range_in(empty, &buffer), Ok(48..64));
+        assert_eq!(
+            range_in(mbuf, &buffer[..32]),
+            Err(MbufError::OffsetOutOfRange {
+                offset: 16,
+                size: 32
+            })
+        );
+        assert!(matches!(
+            range_in(mbuf, &other),
+            Err(MbufError::OffsetOutOfRange { size: 64, .. })
+        ));
+    }
+}
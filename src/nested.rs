@@ -0,0 +1,190 @@
+use std::marker::PhantomData;
+
+use crate::{Mbuf, MbufError, MbufLength, MbufWriter, Pod, Region, U64Le};
+
+/// An Mbuf<M, U64Le, L> whose elements are offsets of Mbuf<M2, D2, L> records,
+/// relative to the start of the NestedMbuf.
+/// <br>The records usually follow the offset table, as written by [`MbufWriter::append_nested`].
+#[repr(transparent)]
+pub struct NestedMbuf<'lt, M, M2, D2, L = usize> {
+    table: Mbuf<'lt, M, U64Le, L>,
+    _marker: PhantomData<&'lt Mbuf<'lt, M2, D2, L>>,
+}
+
+impl<'lt, M, M2, D2, L: MbufLength> NestedMbuf<'lt, M, M2, D2, L> {
+    /// Alignment a NestedMbuf<'lt, M, M2, D2, L> must be placed at for its records to stay aligned.
+    pub const fn required_align() -> usize {
+        let table = Mbuf::<M, U64Le, L>::required_align();
+        let record = Mbuf::<M2, D2, L>::required_align();
+
+        if table > record {
+            table
+        } else {
+            record
+        }
+    }
+
+    /// Declares a NestedMbuf begins at a given pointer
+    /// # Safety
+    /// Safe only if the pointer points to a valid NestedMbuf<'lt, M, M2, D2, L>.
+    /// - Every offset in the table must point to a valid Mbuf<'lt, M2, D2, L>.
+    /// - The memory region at `pointer` must outlive the returned `&NestedMbuf`.
+    pub unsafe fn at_ptr(pointer: *const u8) -> &'lt Self {
+        &*(pointer as *const Self)
+    }
+
+    pub fn get_metadata(&self) -> &M {
+        self.table.get_metadata()
+    }
+
+    /// Number of records.
+    pub fn len(&self) -> usize {
+        self.table.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.table.is_empty()
+    }
+
+    /// Returns the record at `index`, or `None` if out of bounds.
+    pub fn get(&self, index: usize) -> Option<&Mbuf<'lt, M2, D2, L>> {
+        self.table.get(index).map(|offset| self.record(*offset))
+    }
+
+    /// Iterates over all records in order.
+    pub fn iter(&self) -> impl ExactSizeIterator<Item = &Mbuf<'lt, M2, D2, L>> + '_ {
+        self.table.iter().map(|offset| self.record(*offset))
+    }
+
+    fn record(&self, offset: U64Le) -> &Mbuf<'lt, M2, D2, L> {
+        unsafe { Mbuf::at_ptr((self as *const Self as *const u8).add(offset.get() as usize)) }
+    }
+}
+
+impl<'lt, M: Pod, M2: Pod, D2: Pod, L: MbufLength> NestedMbuf<'lt, M, M2, D2, L> {
+    /// Interprets the beginning of `bytes` as a NestedMbuf, checking alignment and bounds of the table
+    /// and every record.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        let table = Mbuf::<M, U64Le, L>::from_bytes(bytes)?;
+
+        for offset in table.iter() {
+            let offset = usize::try_from(offset.get()).unwrap_or(usize::MAX);
+
+            Mbuf::<M2, D2, L>::from_bytes_at(bytes, offset)?;
+        }
+
+        Ok(unsafe { Self::at_ptr(bytes.as_ptr()) })
+    }
+
+    /// Interprets the bytes at `offset` in `region` as a NestedMbuf, checking alignment and bounds.
+    pub fn from_bytes_at(region: &'lt [u8], offset: usize) -> Result<&'lt Self, MbufError> {
+        let bytes = region.get(offset..).ok_or(MbufError::OffsetOutOfRange {
+            offset,
+            size: region.len(),
+        })?;
+
+        Self::from_bytes(bytes)
+    }
+}
+
+impl<R: Region, L: MbufLength> MbufWriter<R, L> {
+    /// Appends a NestedMbuf holding `metadata`, followed by one Mbuf<M2, D2, L> per item of `records`.
+    /// <br>Returns the offset of the NestedMbuf within the region.
+    pub fn append_nested<'a, M, M2, D2, I>(
+        &mut self,
+        metadata: M,
+        records: I,
+    ) -> Result<usize, MbufError>
+    where
+        M: Pod,
+        M2: Pod,
+        D2: Pod + 'a,
+        I: IntoIterator<Item = (M2, &'a [D2])>,
+        I::IntoIter: ExactSizeIterator,
+    {
+        let records = records.into_iter();
+        let count = records.len();
+
+        if L::from_usize(count).is_none() {
+            return Err(MbufError::LengthOverflow { length: count });
+        }
+
+        let start = self.reserve_bytes(
+            Mbuf::<M, U64Le, L>::total_size(count)?,
+            NestedMbuf::<M, M2, D2, L>::required_align(),
+        )?;
+
+        let mut table = Vec::with_capacity(count);
+
+        for (metadata, data) in records {
+            let offset = self.append::<M2, D2>(metadata, data)?;
+
+            table.push(U64Le::new((offset - start) as u64));
+        }
+
+        if table.len() != count {
+            return Err(MbufError::InvalidMetadata("record count mismatch"));
+        }
+
+        Mbuf::<M, U64Le, L>::write_to_bytes_at(self.bytes_mut(), start, metadata, &table)?;
+
+        Ok(start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::tests::write_after_record;
+    use crate::AlignedBuffer;
+
+    type Nested<'lt> = NestedMbuf<'lt, u32, u32, u16>;
+
+    fn write() -> (AlignedBuffer, usize) {
+        let records: [(u32, &[u16]); 3] = [(2, &[1, 2]), (3, &[]), (4, &[3])];
+
+        write_after_record(|writer| writer.append_nested(1u32, records))
+    }
+
+    #[test]
+    fn round_trip() {
+        let (buffer, start) = write();
+        let nested = Nested::from_bytes_at(&buffer, start).unwrap();
+
+        assert_eq!(*nested.get_metadata(), 1);
+        assert_eq!(nested.len(), 3);
+        assert_eq!(*nested.get(0).unwrap().get_metadata(), 2);
+        assert_eq!(&**nested.get(2).unwrap(), &[3]);
+        assert!(nested.get(3).is_none());
+
+        let records: Vec<_> = nested
+            .iter()
+            .map(|mbuf| (*mbuf.get_metadata(), mbuf.to_vec()))
+            .collect();
+        assert_eq!(records, [(2, vec![1, 2]), (3, vec![]), (4, vec![3])]);
+
+        let nested = Nested::from_bytes(&buffer[start..]).unwrap();
+        assert_eq!(nested.iter().len(), 3);
+    }
+
+    #[test]
+    fn rejects_out_of_range_record() {
+        let (mut buffer, start) = write();
+        let size = buffer.len() - start;
+
+        Mbuf::<u32, U64Le>::from_bytes_at_mut(&mut buffer, start).unwrap()[1] =
+            U64Le::new(size as u64 + 8);
+
+        assert_eq!(
+            Nested::from_bytes_at(&buffer, start).err(),
+            Some(MbufError::OffsetOutOfRange {
+                offset: size + 8,
+                size
+            })
+        );
+        assert!(matches!(
+            Nested::from_bytes_at(&buffer, buffer.len() + 1),
+            Err(MbufError::OffsetOutOfRange { .. })
+        ));
+    }
+}
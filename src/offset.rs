@@ -0,0 +1,167 @@
+use std::marker::PhantomData;
+
+use crate::{Mbuf, MbufError, MbufLength, MbufMut, Pod, U64Le};
+
+/// A typed, relocatable reference to an Mbuf<M, D, L>, stored as a little-endian byte offset
+/// from the start of the containing region.
+/// <br>It stays valid when the region is mapped at a different address, and can be stored inside Mbufs.
+#[repr(transparent)]
+pub struct MbufOffset<M, D, L = usize> {
+    offset: U64Le,
+    _marker: PhantomData<fn(M, D, L)>,
+}
+
+unsafe impl<M: 'static, D: 'static, L: 'static> Pod for MbufOffset<M, D, L> {}
+
+impl<M, D, L> MbufOffset<M, D, L> {
+    /// An offset which never resolves.
+    pub const NULL: Self = Self::from_raw(u64::MAX);
+
+    pub const fn new(offset: usize) -> Self {
+        Self::from_raw(offset as u64)
+    }
+
+    pub const fn from_raw(offset: u64) -> Self {
+        Self {
+            offset: U64Le::new(offset),
+            _marker: PhantomData,
+        }
+    }
+
+    pub const fn get(self) -> u64 {
+        self.offset.get()
+    }
+
+    pub const fn is_null(self) -> bool {
+        self.get() == u64::MAX
+    }
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> MbufOffset<M, D, L> {
+    /// Resolves this offset within `region`, checking alignment and bounds.
+    pub fn resolve(self, region: &[u8]) -> Result<&Mbuf<'_, M, D, L>, MbufError> {
+        Mbuf::from_bytes_at(region, self.to_usize(region.len())?)
+    }
+
+    /// Resolves this offset within `region` mutably, checking alignment and bounds.
+    pub fn resolve_mut(self, region: &mut [u8]) -> Result<MbufMut<'_, M, D, L>, MbufError> {
+        let offset = self.to_usize(region.len())?;
+
+        Mbuf::from_bytes_at_mut(region, offset)
+    }
+
+    fn to_usize(self, size: usize) -> Result<usize, MbufError> {
+        usize::try_from(self.get()).map_err(|_| MbufError::OffsetOutOfRange {
+            offset: usize::MAX,
+            size,
+        })
+    }
+}
+
+impl<M, D, L> Clone for MbufOffset<M, D, L> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<M, D, L> Copy for MbufOffset<M, D, L> {}
+
+impl<M, D, L> PartialEq for MbufOffset<M, D, L> {
+    fn eq(&self, other: &Self) -> bool {
+        self.offset == other.offset
+    }
+}
+
+impl<M, D, L> Eq for MbufOffset<M, D, L> {}
+
+impl<M, D, L> std::hash::Hash for MbufOffset<M, D, L> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        self.offset.hash(state);
+    }
+}
+
+impl<M, D, L> std::fmt::Debug for MbufOffset<M, D, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("MbufOffset").field(&self.get()).finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    type Offset = MbufOffset<u32, u16>;
+
+    #[test]
+    fn resolve_bounds() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        Mbuf::<u32, u16>::write_to_bytes_at(&mut buffer, 16, 7, &[1, 2, 3]).unwrap();
+
+        let offset = Offset::new(16);
+        assert_eq!(*offset.resolve(&buffer).unwrap().get_metadata(), 7);
+
+        offset.resolve_mut(&mut buffer).unwrap()[0] = 4;
+        assert_eq!(&**offset.resolve(&buffer).unwrap(), &[4, 2, 3]);
+
+        assert_eq!(
+            offset.resolve(&buffer[..37]).err(),
+            Some(MbufError::RegionTooSmall {
+                required: 22,
+                available: 21
+            })
+        );
+        assert_eq!(
+            Offset::new(65).resolve(&buffer).err(),
+            Some(MbufError::OffsetOutOfRange {
+                offset: 65,
+                size: 64
+            })
+        );
+        assert!(matches!(
+            Offset::new(18).resolve_mut(&mut buffer),
+            Err(MbufError::MisalignedPointer { .. })
+        ));
+    }
+
+    #[test]
+    fn null() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let error = Some(MbufError::OffsetOutOfRange {
+            offset: usize::MAX,
+            size: 64,
+        });
+
+        assert!(Offset::NULL.is_null());
+        assert!(!Offset::new(0).is_null());
+        assert_eq!(Offset::NULL.get(), u64::MAX);
+        assert_eq!(Offset::NULL.resolve(&buffer).err(), error);
+        assert_eq!(Offset::NULL.resolve_mut(&mut buffer).err(), error);
+    }
+
+    #[test]
+    fn to_usize_overflow() {
+        let raw = u64::from(u32::MAX) + 16;
+        let offset = Offset::from_raw(raw);
+
+        // an offset which does not fit into usize on 32-bit targets is reported as usize::MAX
+        let expected = usize::try_from(raw).unwrap_or(usize::MAX);
+
+        assert_eq!(offset.get(), raw);
+        assert_eq!(offset.to_usize(64).unwrap_or(usize::MAX), expected);
+        assert_eq!(
+            offset.resolve(&AlignedBuffer::zeroed(64)).err(),
+            Some(MbufError::OffsetOutOfRange {
+                offset: expected,
+                size: 64
+            })
+        );
+        assert_eq!(
+            Offset::from_raw(u64::MAX).to_usize(64),
+            usize::try_from(u64::MAX).map_err(|_| MbufError::OffsetOutOfRange {
+                offset: usize::MAX,
+                size: 64
+            })
+        );
+    }
+}
@@ -0,0 +1,96 @@
+use crate::MbufError;
+
+/// Marker for types that may be reinterpreted from arbitrary bytes.
+/// # Safety
+/// Implement only for types where:
+/// - every bit pattern is a valid value,
+/// - there are no padding bytes,
+/// - there are no pointers, references or other invariants tied to the current process.
+pub unsafe trait Pod: Copy + 'static {}
+
+macro_rules! impl_pod {
+    ($($t:ty),* $(,)?) => {
+        $(unsafe impl Pod for $t {})*
+    };
+}
+
+impl_pod!(
+    u8,
+    u16,
+    u32,
+    u64,
+    u128,
+    usize,
+    i8,
+    i16,
+    i32,
+    i64,
+    i128,
+    isize,
+    f32,
+    f64,
+    ()
+);
+
+unsafe impl<T: Pod, const N: usize> Pod for [T; N] {}
+
+/// Marker for generic `repr(C)` types of [`Pod`] fields, which are plain data as long as
+/// they contain no padding, e.g. [`crate::Bucket`].
+/// # Safety
+/// Implement only for `repr(C)` types whose fields are all [`Pod`], with `NO_PADDING` failing
+/// to evaluate unless the size of the type is the sum of the sizes of its fields.
+pub(crate) unsafe trait PaddingFree: Copy + 'static {
+    const NO_PADDING: ();
+}
+
+/// Interprets the bytes at `offset` in `region` as a `T`, checking alignment and bounds.
+pub(crate) fn pod_ref<T: Pod>(region: &[u8], offset: usize) -> Result<&T, MbufError> {
+    pod_check::<T>(region, offset)?;
+
+    Ok(unsafe { &*(region.as_ptr().add(offset) as *const T) })
+}
+
+/// Interprets the bytes at `offset` in `region` as a mutable `T`, checking alignment and bounds.
+pub(crate) fn pod_mut<T: Pod>(region: &mut [u8], offset: usize) -> Result<&mut T, MbufError> {
+    pod_check::<T>(region, offset)?;
+
+    Ok(unsafe { &mut *(region.as_mut_ptr().add(offset) as *mut T) })
+}
+
+fn pod_check<T: Pod>(region: &[u8], offset: usize) -> Result<(), MbufError> {
+    let size = region.len();
+    let bytes = region
+        .get(offset..)
+        .ok_or(MbufError::OffsetOutOfRange { offset, size })?;
+
+    let address = bytes.as_ptr() as usize;
+    let alignment = std::mem::align_of::<T>();
+
+    if !address.is_multiple_of(alignment) {
+        return Err(MbufError::MisalignedPointer {
+            address,
+            align: alignment,
+        });
+    }
+
+    if bytes.len() < std::mem::size_of::<T>() {
+        return Err(MbufError::RegionTooSmall {
+            required: std::mem::size_of::<T>(),
+            available: bytes.len(),
+        });
+    }
+
+    Ok(())
+}
+
+/// Views `values` as raw bytes.
+pub(crate) fn bytes_of_slice<T: Pod>(values: &[T]) -> &[u8] {
+    unsafe {
+        std::slice::from_raw_parts(values.as_ptr() as *const u8, std::mem::size_of_val(values))
+    }
+}
+
+/// Views `value` as raw bytes.
+pub(crate) fn bytes_of<T: Pod>(value: &T) -> &[u8] {
+    bytes_of_slice(std::slice::from_ref(value))
+}
@@ -0,0 +1,152 @@
+use std::marker::PhantomData;
+
+use crate::{align_up, Mbuf, MbufError, MbufLength, Pod, RegionHeader};
+
+/// Iterates over consecutive Mbuf<M, D, L> records in a region, as laid out by [`crate::MbufWriter`].
+/// <br>Yields the offset of each record along with the record itself, and stops after the first error.
+/// <br>Reading stops once the space left after aligning cannot hold the metadata and length of
+/// another record; pass the end of the written records to [`MbufReader::with_end`] to stop there.
+pub struct MbufReader<'lt, M, D, L = usize> {
+    region: &'lt [u8],
+    position: usize,
+    _marker: PhantomData<(M, D, L)>,
+}
+
+impl<'lt, M: Pod, D: Pod, L: MbufLength> MbufReader<'lt, M, D, L> {
+    /// Creates a reader which starts at the beginning of `region`.
+    pub fn new(region: &'lt [u8]) -> Self {
+        Self::with_position(region, 0)
+    }
+
+    /// Creates a reader which starts at byte `position` of `region`.
+    pub fn with_position(region: &'lt [u8], position: usize) -> Self {
+        Self {
+            region,
+            position,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Verifies the [`RegionHeader`] at the beginning of `region` and starts after it.
+    pub fn with_header(region: &'lt [u8]) -> Result<Self, MbufError> {
+        RegionHeader::read::<M, D, L>(region)?;
+
+        Ok(Self::with_position(
+            region,
+            std::mem::size_of::<RegionHeader>(),
+        ))
+    }
+
+    /// Stops reading at byte `end` of the region, e.g. at [`crate::MbufWriter::position`].
+    /// <br>A record extending past `end` is reported as an error.
+    pub fn with_end(mut self, end: usize) -> Self {
+        self.region = &self.region[..end.min(self.region.len())];
+
+        self
+    }
+
+    /// Offset the next record will be searched for at.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    fn read_next(&mut self, offset: usize) -> Result<(usize, &'lt Mbuf<'lt, M, D, L>), MbufError> {
+        let mbuf = Mbuf::<M, D, L>::from_bytes_at(self.region, offset)?;
+
+        self.position = offset + Mbuf::<M, D, L>::total_size(mbuf.len())?;
+
+        Ok((offset, mbuf))
+    }
+}
+
+impl<'lt, M: Pod, D: Pod, L: MbufLength> Iterator for MbufReader<'lt, M, D, L> {
+    type Item = Result<(usize, &'lt Mbuf<'lt, M, D, L>), MbufError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = align_up(self.position, Mbuf::<M, D, L>::required_align());
+        let remaining = self.region.len().saturating_sub(offset);
+
+        if remaining < std::mem::size_of::<Mbuf<M, D, L>>() {
+            self.position = self.region.len();
+
+            return None;
+        }
+
+        let item = self.read_next(offset);
+
+        if item.is_err() {
+            self.position = self.region.len();
+        }
+
+        Some(item)
+    }
+}
+
+impl<M: Pod, D: Pod, L: MbufLength> std::iter::FusedIterator for MbufReader<'_, M, D, L> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlignedBuffer, MbufWriter};
+
+    fn records(region: &[u8]) -> Vec<Result<(usize, u32, usize), MbufError>> {
+        MbufReader::<u32, u16>::new(region)
+            .map(|item| item.map(|(offset, mbuf)| (offset, *mbuf.get_metadata(), mbuf.len())))
+            .collect()
+    }
+
+    #[test]
+    fn reads_aligned_records() {
+        let mut writer = MbufWriter::new(AlignedBuffer::new());
+        let offsets: Vec<usize> = (0..5u32)
+            .map(|i| writer.append(i, &vec![i as u16; i as usize]).unwrap())
+            .collect();
+        let bytes = writer.into_inner();
+        let expected: Vec<_> = (0..5).map(|i| Ok((offsets[i], i as u32, i))).collect();
+
+        assert_eq!(records(&bytes), expected);
+    }
+
+    #[test]
+    fn stops_after_truncated_record() {
+        let mut writer = MbufWriter::new(AlignedBuffer::new());
+        writer.append(1u32, &[1u16, 2]).unwrap();
+        writer.append(2u32, &[3u16, 4]).unwrap();
+        let bytes = writer.into_inner();
+        let records = records(&bytes[..bytes.len() - 1]);
+
+        assert_eq!(records.len(), 2);
+        assert!(records[1].is_err());
+    }
+
+    #[test]
+    fn stops_in_trailing_padding() {
+        for size in [24, 30] {
+            let mut buffer = AlignedBuffer::zeroed(size);
+            let mut writer = MbufWriter::new(&mut buffer[..]);
+            writer.append(1u32, &[1u16, 2, 3]).unwrap();
+            assert_eq!(writer.position(), 22);
+
+            assert_eq!(records(&buffer), [Ok((0, 1, 3))]);
+        }
+    }
+
+    #[test]
+    fn with_end() {
+        let mut writer = MbufWriter::new(AlignedBuffer::zeroed(256));
+        writer.append(1u32, &[1u16, 2]).unwrap();
+        let end = writer.position();
+        let bytes = writer.into_inner();
+
+        let records: Vec<_> = MbufReader::<u32, u16>::new(&bytes)
+            .with_end(end)
+            .collect::<Result<_, _>>()
+            .unwrap();
+        assert_eq!(records.len(), 1);
+        assert_eq!(&**records[0].1, &[1, 2]);
+
+        let mut truncated = MbufReader::<u32, u16>::new(&bytes).with_end(end - 1);
+        assert!(truncated.next().unwrap().is_err());
+        assert!(truncated.next().is_none());
+    }
+}
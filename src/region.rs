@@ -0,0 +1,28 @@
+use crate::MbufError;
+
+/// Byte storage that Mbufs are written into and read from.
+/// <br>Implemented for fixed-size byte slices, for [`crate::AlignedBuffer`] and, with the `mmap` feature,
+/// for memory maps and `FileRegion`.
+pub trait Region {
+    fn bytes(&self) -> &[u8];
+
+    fn bytes_mut(&mut self) -> &mut [u8];
+
+    /// Grows the region to at least `size` bytes; fixed-size regions return an error.
+    fn grow(&mut self, size: usize) -> Result<(), MbufError> {
+        Err(MbufError::RegionTooSmall {
+            required: size,
+            available: self.bytes().len(),
+        })
+    }
+}
+
+impl Region for &mut [u8] {
+    fn bytes(&self) -> &[u8] {
+        self
+    }
+
+    fn bytes_mut(&mut self) -> &mut [u8] {
+        self
+    }
+}
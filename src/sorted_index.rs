@@ -0,0 +1,284 @@
+use std::ops::{Bound, RangeBounds};
+
+use crate::pod::PaddingFree;
+use crate::{Mbuf, MbufError, MbufLength, MbufWriter, Pod, Region};
+
+/// A key and its value in an [`MbufSortedIndex`].
+/// <br>Like a [`crate::Bucket`], it must not contain padding and does not implement [`Pod`].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(C)]
+pub struct IndexEntry<K, V> {
+    key: K,
+    value: V,
+}
+
+unsafe impl<K: Pod, V: Pod> PaddingFree for IndexEntry<K, V> {
+    const NO_PADDING: () = assert!(
+        std::mem::size_of::<Self>() == std::mem::size_of::<K>() + std::mem::size_of::<V>(),
+        "IndexEntry<K, V> must not contain padding"
+    );
+}
+
+impl<K: Pod, V: Pod> IndexEntry<K, V> {
+    pub fn key(&self) -> &K {
+        &self.key
+    }
+
+    pub fn value(&self) -> &V {
+        &self.value
+    }
+}
+
+/// A static sorted index stored as an Mbuf<(), IndexEntry<K, V>, L> in Eytzinger order,
+/// i.e. as an implicit binary search tree whose node `k` has the children `2k` and `2k + 1`.
+/// <br>Queries run directly on the stored bytes and need no heap allocation.
+/// <br>If the entries were not written by [`MbufWriter::append_sorted_index`],
+/// query results are unspecified, but never unsound.
+#[repr(transparent)]
+pub struct MbufSortedIndex<'lt, K, V, L = usize> {
+    inner: Mbuf<'lt, (), IndexEntry<K, V>, L>,
+}
+
+impl<'lt, K: Pod + Ord, V: Pod, L: MbufLength> MbufSortedIndex<'lt, K, V, L> {
+    pub fn from_mbuf<'a>(mbuf: &'a Mbuf<'lt, (), IndexEntry<K, V>, L>) -> &'a Self {
+        let () = IndexEntry::<K, V>::NO_PADDING;
+
+        unsafe { &*(mbuf as *const Mbuf<'lt, (), IndexEntry<K, V>, L> as *const Self) }
+    }
+
+    /// Interprets the beginning of `bytes` as an MbufSortedIndex, checking alignment and bounds.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::from_bytes_at(bytes, 0)
+    }
+
+    /// Interprets the bytes at `offset` in `region` as an MbufSortedIndex, checking alignment and bounds.
+    pub fn from_bytes_at(region: &'lt [u8], offset: usize) -> Result<&'lt Self, MbufError> {
+        // the fields of an entry are Pod
+        Ok(Self::from_mbuf(unsafe {
+            Mbuf::from_bytes_at_unchecked(region, offset)?
+        }))
+    }
+
+    /// Number of entries.
+    pub fn len(&self) -> usize {
+        self.inner.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.inner.is_empty()
+    }
+
+    /// Returns the value of the first entry with key `key`.
+    pub fn get(&self, key: &K) -> Option<&V> {
+        self.lower_bound(key)
+            .next()
+            .filter(|(found, _)| *found == key)
+            .map(|(_, value)| value)
+    }
+
+    /// Iterates in key order, starting at the first entry whose key is not less than `key`.
+    pub fn lower_bound(&self, key: &K) -> SortedIndexIter<'_, K, V> {
+        self.search(|probe| probe < key)
+    }
+
+    /// Iterates in key order, starting at the first entry whose key is greater than `key`.
+    pub fn upper_bound(&self, key: &K) -> SortedIndexIter<'_, K, V> {
+        self.search(|probe| probe <= key)
+    }
+
+    /// Iterates in key order over all entries whose keys lie within `range`.
+    pub fn range(&self, range: impl RangeBounds<K>) -> impl Iterator<Item = (&K, &V)> {
+        let start = match range.start_bound() {
+            Bound::Included(key) => self.lower_bound(key),
+            Bound::Excluded(key) => self.upper_bound(key),
+            Bound::Unbounded => self.iter(),
+        };
+
+        let end = range.end_bound().cloned();
+
+        start.take_while(move |(key, _)| match &end {
+            Bound::Included(end) => *key <= end,
+            Bound::Excluded(end) => *key < end,
+            Bound::Unbounded => true,
+        })
+    }
+
+    /// Iterates over all entries in key order.
+    pub fn iter(&self) -> SortedIndexIter<'_, K, V> {
+        SortedIndexIter {
+            entries: &self.inner,
+            node: leftmost(1, self.len()),
+        }
+    }
+
+    /// Finds the first node in key order for which `less` is false.
+    fn search(&self, less: impl Fn(&K) -> bool) -> SortedIndexIter<'_, K, V> {
+        let entries: &[IndexEntry<K, V>] = &self.inner;
+        let mut node = 1;
+
+        while node <= entries.len() {
+            node = 2 * node + usize::from(less(&entries[node - 1].key));
+        }
+
+        // undo the right turns taken after the last left turn, and the left turn itself
+        node >>= node.trailing_ones() + 1;
+
+        SortedIndexIter { entries, node }
+    }
+}
+
+/// Iterator over the entries of an [`MbufSortedIndex`] in key order.
+pub struct SortedIndexIter<'a, K, V> {
+    entries: &'a [IndexEntry<K, V>],
+    node: usize,
+}
+
+impl<'a, K, V> Iterator for SortedIndexIter<'a, K, V> {
+    type Item = (&'a K, &'a V);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let entry = self.entries.get(self.node.checked_sub(1)?)?;
+
+        self.node = successor(self.node, self.entries.len());
+
+        Some((&entry.key, &entry.value))
+    }
+}
+
+impl<K, V> std::iter::FusedIterator for SortedIndexIter<'_, K, V> {}
+
+/// The leftmost node in the subtree rooted at `node`, or 0 if the subtree is empty.
+fn leftmost(mut node: usize, len: usize) -> usize {
+    if node > len {
+        return 0;
+    }
+
+    while 2 * node <= len {
+        node *= 2;
+    }
+
+    node
+}
+
+/// The next node in key order after `node`, or 0 if there is none.
+fn successor(node: usize, len: usize) -> usize {
+    if 2 * node < len {
+        return leftmost(2 * node + 1, len);
+    }
+
+    // climb while coming from a right child, then once more
+    (node >> node.trailing_ones()) >> 1
+}
+
+impl<R: Region, L: MbufLength> MbufWriter<R, L> {
+    /// Appends an [`MbufSortedIndex`] holding `entries`, returning its offset within the region.
+    /// <br>Entries with equal keys are kept in the order given.
+    pub fn append_sorted_index<K: Pod + Ord, V: Pod>(
+        &mut self,
+        entries: impl IntoIterator<Item = (K, V)>,
+    ) -> Result<usize, MbufError> {
+        let () = IndexEntry::<K, V>::NO_PADDING;
+        let mut sorted: Vec<IndexEntry<K, V>> = entries
+            .into_iter()
+            .map(|(key, value)| IndexEntry { key, value })
+            .collect();
+
+        sorted.sort_by_key(|entry| entry.key);
+
+        let len = sorted.len();
+        let mut layout = sorted.clone();
+        let mut node = leftmost(1, len);
+
+        for entry in sorted {
+            layout[node - 1] = entry;
+            node = successor(node, len);
+        }
+
+        self.append_padding_free((), &layout)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::writer::tests::write_after_record;
+    use crate::AlignedBuffer;
+
+    fn write(entries: &[(u32, u32)]) -> (AlignedBuffer, usize) {
+        write_after_record(|writer| writer.append_sorted_index(entries.iter().copied()))
+    }
+
+    fn index((buffer, offset): &(AlignedBuffer, usize)) -> &MbufSortedIndex<'_, u32, u32> {
+        MbufSortedIndex::from_bytes_at(buffer, *offset).unwrap()
+    }
+
+    fn keys<'a>(entries: impl Iterator<Item = (&'a u32, &'a u32)>) -> Vec<u32> {
+        entries.map(|(key, _)| *key).collect()
+    }
+
+    #[test]
+    fn round_trip() {
+        for n in 0..40u32 {
+            let entries: Vec<(u32, u32)> = (0..n).rev().map(|i| (i * 2, i)).collect();
+            let written = write(&entries);
+            let index = index(&written);
+            let expected: Vec<u32> = (0..n).map(|i| i * 2).collect();
+
+            assert_eq!(index.len(), n as usize);
+            assert_eq!(keys(index.iter()), expected);
+
+            for query in 0..2 * n + 2 {
+                let lower = expected.iter().copied().find(|key| *key >= query);
+                let upper = expected.iter().copied().find(|key| *key > query);
+
+                assert_eq!(index.lower_bound(&query).next().map(|(key, _)| *key), lower);
+                assert_eq!(index.upper_bound(&query).next().map(|(key, _)| *key), upper);
+                assert_eq!(
+                    index.get(&query).copied(),
+                    (query % 2 == 0 && query / 2 < n).then_some(query / 2)
+                );
+            }
+
+            assert_eq!(
+                keys(index.range(3..=10)),
+                expected
+                    .iter()
+                    .copied()
+                    .filter(|key| (3..=10).contains(key))
+                    .collect::<Vec<_>>()
+            );
+            assert_eq!(
+                keys(index.range(..7)),
+                expected
+                    .iter()
+                    .copied()
+                    .filter(|key| *key < 7)
+                    .collect::<Vec<_>>()
+            );
+        }
+    }
+
+    #[test]
+    fn empty() {
+        let written = write(&[]);
+        let index = index(&written);
+
+        assert!(index.is_empty());
+        assert_eq!(index.get(&0), None);
+        assert_eq!(index.iter().count(), 0);
+        assert_eq!(index.lower_bound(&0).count(), 0);
+    }
+
+    #[test]
+    fn duplicate_keys_keep_order() {
+        let written = write(&[(5, 0), (1, 1), (5, 2), (5, 3), (3, 4)]);
+        let index = index(&written);
+        let entries: Vec<(u32, u32)> = index.iter().map(|(key, value)| (*key, *value)).collect();
+
+        assert_eq!(entries, [(1, 1), (3, 4), (5, 0), (5, 2), (5, 3)]);
+        assert_eq!(index.lower_bound(&5).next(), Some((&5, &0)));
+        assert_eq!(index.upper_bound(&3).next(), Some((&5, &0)));
+        assert_eq!(index.range(5..).count(), 3);
+        assert!(matches!(index.get(&5), Some(0 | 2 | 3)));
+    }
+}
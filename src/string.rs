@@ -0,0 +1,292 @@
+use std::ffi::CStr;
+
+use crate::{Mbuf, MbufError, MbufLength, Pod};
+
+/// An Mbuf<M, u8, L> whose data is valid UTF-8.
+/// <br>Validated once on construction, it dereferences to `str`.
+/// <br>It is read-only; modify the Mbuf through an [`crate::MbufMut`] and validate it again.
+#[repr(transparent)]
+pub struct MbufStr<'lt, M, L = usize> {
+    inner: Mbuf<'lt, M, u8, L>,
+}
+
+impl<'lt, M, L: MbufLength> MbufStr<'lt, M, L> {
+    /// Validates that the data of `mbuf` is UTF-8.
+    pub fn from_mbuf<'a>(mbuf: &'a Mbuf<'lt, M, u8, L>) -> Result<&'a Self, MbufError> {
+        validate_utf8(mbuf)?;
+
+        Ok(unsafe { Self::from_mbuf_unchecked(mbuf) })
+    }
+
+    /// # Safety
+    /// The data of `mbuf` must be valid UTF-8.
+    pub unsafe fn from_mbuf_unchecked<'a>(mbuf: &'a Mbuf<'lt, M, u8, L>) -> &'a Self {
+        &*(mbuf as *const Mbuf<'lt, M, u8, L> as *const Self)
+    }
+
+    /// Declares an Mbuf at `pointer` and copies `metadata` and the bytes of `string` into it.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `string`.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - The memory region at `pointer` must outlive the returned `&MbufStr`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_str(pointer: *mut u8, metadata: M, string: &str) -> &'lt Self {
+        Self::from_mbuf_unchecked(Mbuf::write_to_ptr(pointer, metadata, string.as_bytes()))
+    }
+
+    pub fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(&self.inner) }
+    }
+
+    pub fn as_mbuf(&self) -> &Mbuf<'lt, M, u8, L> {
+        &self.inner
+    }
+
+    pub fn get_metadata(&self) -> &M {
+        self.inner.get_metadata()
+    }
+}
+
+impl<'lt, M: Pod, L: MbufLength> MbufStr<'lt, M, L> {
+    /// Interprets the beginning of `bytes` as an MbufStr, checking alignment, bounds and UTF-8.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::from_mbuf(Mbuf::from_bytes(bytes)?)
+    }
+
+    /// Copies `metadata` and the bytes of `string` into the beginning of `bytes`, checking alignment and bounds.
+    pub fn write_str_to_bytes(
+        bytes: &'lt mut [u8],
+        metadata: M,
+        string: &str,
+    ) -> Result<&'lt Self, MbufError> {
+        Mbuf::<M, u8, L>::check_fits(bytes.as_ptr(), bytes.len(), string.len())?;
+
+        Ok(unsafe { Self::write_str(bytes.as_mut_ptr(), metadata, string) })
+    }
+}
+
+/// An Mbuf<M, u8, L> whose data is valid UTF-8 followed by a single trailing NUL byte.
+/// <br>Validated once on construction, it dereferences to `str` without the NUL byte,
+/// and converts to [`CStr`] with it.
+#[repr(transparent)]
+pub struct MbufCStr<'lt, M, L = usize> {
+    inner: Mbuf<'lt, M, u8, L>,
+}
+
+impl<'lt, M, L: MbufLength> MbufCStr<'lt, M, L> {
+    /// Validates that the data of `mbuf` is UTF-8 terminated by its only NUL byte.
+    pub fn from_mbuf<'a>(mbuf: &'a Mbuf<'lt, M, u8, L>) -> Result<&'a Self, MbufError> {
+        match mbuf.iter().position(|byte| *byte == 0) {
+            Some(position) if position + 1 == mbuf.len() => {
+                validate_utf8(&mbuf[..position])?;
+            }
+            Some(position) => return Err(MbufError::InteriorNul { position }),
+            None => return Err(MbufError::MissingNul),
+        }
+
+        Ok(unsafe { Self::from_mbuf_unchecked(mbuf) })
+    }
+
+    /// # Safety
+    /// The data of `mbuf` must be valid UTF-8 terminated by its only NUL byte.
+    pub unsafe fn from_mbuf_unchecked<'a>(mbuf: &'a Mbuf<'lt, M, u8, L>) -> &'a Self {
+        &*(mbuf as *const Mbuf<'lt, M, u8, L> as *const Self)
+    }
+
+    /// Declares an Mbuf at `pointer` and copies `metadata`, the bytes of `string` and a NUL byte into it.
+    /// # Safety
+    /// - `pointer` must point to a large enough place in memory to hold `metadata` + `L` + `string` + 1.
+    /// - `pointer` must be aligned to at least `L` and at least `M`.
+    /// - `string` must not contain NUL bytes.
+    /// - The memory region at `pointer` must outlive the returned `&MbufCStr`.
+    /// - The memory region at `pointer` must be writable
+    pub unsafe fn write_str(pointer: *mut u8, metadata: M, string: &str) -> &'lt Self {
+        let mbuf = Mbuf::<M, u8, L>::write_to_ptr_mut(pointer, metadata, string.as_bytes());
+
+        (mbuf.data_pointer() as *mut u8).add(string.len()).write(0);
+        mbuf.set_len(string.len() + 1);
+
+        Self::from_mbuf_unchecked(mbuf)
+    }
+
+    /// The string without its trailing NUL byte.
+    pub fn as_str(&self) -> &str {
+        unsafe { std::str::from_utf8_unchecked(&self.inner[..self.inner.len() - 1]) }
+    }
+
+    pub fn as_c_str(&self) -> &CStr {
+        unsafe { CStr::from_bytes_with_nul_unchecked(&self.inner) }
+    }
+
+    pub fn as_mbuf(&self) -> &Mbuf<'lt, M, u8, L> {
+        &self.inner
+    }
+
+    pub fn get_metadata(&self) -> &M {
+        self.inner.get_metadata()
+    }
+}
+
+impl<'lt, M: Pod, L: MbufLength> MbufCStr<'lt, M, L> {
+    /// Interprets the beginning of `bytes` as an MbufCStr, checking alignment, bounds, NUL termination and UTF-8.
+    pub fn from_bytes(bytes: &'lt [u8]) -> Result<&'lt Self, MbufError> {
+        Self::from_mbuf(Mbuf::from_bytes(bytes)?)
+    }
+
+    /// Copies `metadata`, the bytes of `string` and a NUL byte into the beginning of `bytes`,
+    /// checking alignment, bounds and that `string` contains no NUL bytes.
+    pub fn write_str_to_bytes(
+        bytes: &'lt mut [u8],
+        metadata: M,
+        string: &str,
+    ) -> Result<&'lt Self, MbufError> {
+        if let Some(position) = string.bytes().position(|byte| byte == 0) {
+            return Err(MbufError::InteriorNul { position });
+        }
+
+        let length = string
+            .len()
+            .checked_add(1)
+            .ok_or(MbufError::LengthOverflow { length: usize::MAX })?;
+
+        Mbuf::<M, u8, L>::check_fits(bytes.as_ptr(), bytes.len(), length)?;
+
+        Ok(unsafe { Self::write_str(bytes.as_mut_ptr(), metadata, string) })
+    }
+}
+
+fn validate_utf8(bytes: &[u8]) -> Result<(), MbufError> {
+    std::str::from_utf8(bytes)
+        .map(|_| ())
+        .map_err(|err| MbufError::InvalidUtf8 {
+            valid_up_to: err.valid_up_to(),
+        })
+}
+
+impl<M, L: MbufLength> std::ops::Deref for MbufStr<'_, M, L> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<M, L: MbufLength> std::ops::Deref for MbufCStr<'_, M, L> {
+    type Target = str;
+
+    fn deref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl<M, L: MbufLength> AsRef<CStr> for MbufCStr<'_, M, L> {
+    fn as_ref(&self) -> &CStr {
+        self.as_c_str()
+    }
+}
+
+impl<M, L: MbufLength> std::fmt::Debug for MbufStr<'_, M, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<M, L: MbufLength> std::fmt::Display for MbufStr<'_, M, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<M, L: MbufLength> std::fmt::Debug for MbufCStr<'_, M, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+impl<M, L: MbufLength> std::fmt::Display for MbufCStr<'_, M, L> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.as_str().fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    fn record(data: &[u8]) -> AlignedBuffer {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        Mbuf::<u32, u8>::write_to_bytes(&mut buffer, 7, data).unwrap();
+
+        buffer
+    }
+
+    #[test]
+    fn str_round_trip() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        let string = MbufStr::<u32>::write_str_to_bytes(&mut buffer, 7, "héllo").unwrap();
+        assert_eq!(&**string, "héllo");
+
+        let string = MbufStr::<u32>::from_bytes(&buffer).unwrap();
+        assert_eq!(*string.get_metadata(), 7);
+        assert_eq!(string.to_string(), "héllo");
+        assert_eq!(string.as_mbuf().len(), 6);
+    }
+
+    #[test]
+    fn c_str_round_trip() {
+        let mut buffer = AlignedBuffer::zeroed(64);
+        MbufCStr::<u32>::write_str_to_bytes(&mut buffer, 7, "hello").unwrap();
+
+        let string = MbufCStr::<u32>::from_bytes(&buffer).unwrap();
+        assert_eq!(&**string, "hello");
+        assert_eq!(string.as_c_str(), c"hello");
+        assert_eq!(string.as_mbuf().len(), 6);
+
+        assert_eq!(
+            MbufCStr::<u32>::write_str_to_bytes(&mut buffer[..20], 7, "hello").err(),
+            Some(MbufError::RegionTooSmall {
+                required: 22,
+                available: 20
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_utf8() {
+        assert_eq!(
+            MbufStr::<u32>::from_bytes(&record(b"ab\xffc")).err(),
+            Some(MbufError::InvalidUtf8 { valid_up_to: 2 })
+        );
+        assert_eq!(
+            MbufCStr::<u32>::from_bytes(&record(b"a\xff\0")).err(),
+            Some(MbufError::InvalidUtf8 { valid_up_to: 1 })
+        );
+    }
+
+    #[test]
+    fn rejects_misplaced_nul() {
+        assert_eq!(
+            MbufCStr::<u32>::from_bytes(&record(b"a\0b\0")).err(),
+            Some(MbufError::InteriorNul { position: 1 })
+        );
+        assert_eq!(
+            MbufCStr::<u32>::from_bytes(&record(b"abc")).err(),
+            Some(MbufError::MissingNul)
+        );
+        assert_eq!(
+            MbufCStr::<u32>::from_bytes(&record(b"")).err(),
+            Some(MbufError::MissingNul)
+        );
+        assert_eq!(
+            MbufCStr::<u32>::write_str_to_bytes(&mut AlignedBuffer::zeroed(64), 7, "ab\0c").err(),
+            Some(MbufError::InteriorNul { position: 2 })
+        );
+
+        // the data of an MbufStr may contain NUL bytes
+        assert_eq!(
+            &**MbufStr::<u32>::from_bytes(&record(b"a\0b")).unwrap(),
+            "a\0b"
+        );
+    }
+}
@@ -0,0 +1,169 @@
+use std::collections::HashMap;
+use std::marker::PhantomData;
+
+use crate::pod::{pod_mut, pod_ref};
+use crate::{
+    MbufError, MbufLength, MbufOffset, MbufReader, MbufStr, MbufWriter, Region, RegionHeader,
+};
+
+/// Offset of the `u64` holding the end of the last string, which follows the [`RegionHeader`].
+const END_OFFSET: usize = std::mem::size_of::<RegionHeader>();
+
+/// Offset of the first string.
+const DATA_START: usize = END_OFFSET + std::mem::size_of::<u64>();
+
+/// Identifies a string interned into a string table by the offset of its Mbuf<(), u8, L>.
+pub type StringId<L = usize> = MbufOffset<(), u8, L>;
+
+/// Appends deduplicated strings to a region as Mbuf<(), u8, L> records following a [`RegionHeader`]
+/// and the offset at which the records end.
+/// <br>Every distinct string is stored once; interning it again returns the same [`StringId`].
+pub struct StringTableWriter<R, L = usize> {
+    writer: MbufWriter<R, L>,
+    interned: HashMap<Box<str>, StringId<L>>,
+}
+
+impl<R: Region> StringTableWriter<R> {
+    /// Creates a string table at the beginning of `region`.
+    pub fn new(region: R) -> Result<Self, MbufError> {
+        Self::from_region(region)
+    }
+}
+
+impl<R: Region, L: MbufLength> StringTableWriter<R, L> {
+    /// Creates a string table at the beginning of `region`, with lengths stored as `L`.
+    pub fn from_region(region: R) -> Result<Self, MbufError> {
+        let mut writer = MbufWriter::new(region).with_length_type::<L>();
+
+        writer.write_header::<(), u8>()?;
+        writer.reserve_bytes(std::mem::size_of::<u64>(), std::mem::align_of::<u64>())?;
+
+        let mut table = Self {
+            writer,
+            interned: HashMap::new(),
+        };
+
+        table.store_end()?;
+
+        Ok(table)
+    }
+
+    /// Returns the id of `string`, appending it to the region if it was not interned before.
+    pub fn intern(&mut self, string: &str) -> Result<StringId<L>, MbufError> {
+        if let Some(id) = self.interned.get(string) {
+            return Ok(*id);
+        }
+
+        let id = StringId::new(self.writer.append::<(), u8>((), string.as_bytes())?);
+
+        self.store_end()?;
+        self.interned.insert(string.into(), id);
+
+        Ok(id)
+    }
+
+    /// Number of distinct strings interned.
+    pub fn len(&self) -> usize {
+        self.interned.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.interned.is_empty()
+    }
+
+    /// Number of bytes written so far, including padding.
+    pub fn position(&self) -> usize {
+        self.writer.position()
+    }
+
+    pub fn into_inner(self) -> R {
+        self.writer.into_inner()
+    }
+
+    fn store_end(&mut self) -> Result<(), MbufError> {
+        let end = self.writer.position() as u64;
+
+        *pod_mut::<u64>(self.writer.bytes_mut(), END_OFFSET)? = end;
+
+        Ok(())
+    }
+}
+
+/// Read side of a string table written by [`StringTableWriter`], resolving ids without allocating.
+#[derive(Clone, Copy)]
+pub struct StringTable<'lt, L = usize> {
+    region: &'lt [u8],
+    _marker: PhantomData<L>,
+}
+
+impl<'lt> StringTable<'lt> {
+    /// Verifies the [`RegionHeader`] of the string table in `region`.
+    pub fn new(region: &'lt [u8]) -> Result<Self, MbufError> {
+        Self::from_bytes(region)
+    }
+}
+
+impl<'lt, L: MbufLength> StringTable<'lt, L> {
+    /// Verifies the [`RegionHeader`] of the string table in `region`, with lengths stored as `L`.
+    pub fn from_bytes(region: &'lt [u8]) -> Result<Self, MbufError> {
+        RegionHeader::read::<(), u8, L>(region)?;
+
+        let end = usize::try_from(*pod_ref::<u64>(region, END_OFFSET)?).unwrap_or(usize::MAX);
+
+        if end < DATA_START || end > region.len() {
+            return Err(MbufError::InvalidMetadata("string table end out of range"));
+        }
+
+        Ok(Self {
+            region: &region[..end],
+            _marker: PhantomData,
+        })
+    }
+
+    /// Returns the string identified by `id`, checking bounds and UTF-8.
+    pub fn get(&self, id: StringId<L>) -> Result<&'lt str, MbufError> {
+        if id.get() < DATA_START as u64 {
+            return Err(MbufError::OffsetOutOfRange {
+                offset: id.get() as usize,
+                size: self.region.len(),
+            });
+        }
+
+        Ok(MbufStr::from_mbuf(id.resolve(self.region)?)?.as_str())
+    }
+
+    /// Iterates over all interned strings along with their ids, in the order they were interned.
+    pub fn iter(&self) -> impl Iterator<Item = Result<(StringId<L>, &'lt str), MbufError>> + 'lt {
+        let reader = MbufReader::<(), u8, L>::with_position(self.region, DATA_START);
+
+        reader.map(|item| {
+            let (offset, mbuf) = item?;
+
+            Ok((StringId::new(offset), MbufStr::from_mbuf(mbuf)?.as_str()))
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AlignedBuffer, U32Le};
+
+    #[test]
+    fn round_trip() {
+        let mut writer =
+            StringTableWriter::<_, U32Le>::from_region(AlignedBuffer::zeroed(256)).unwrap();
+        let hello = writer.intern("hello").unwrap();
+        let world = writer.intern("world").unwrap();
+
+        assert_eq!(writer.intern("hello").unwrap(), hello);
+        assert_eq!(writer.len(), 2);
+
+        let buffer = writer.into_inner();
+        let table = StringTable::<U32Le>::from_bytes(&buffer).unwrap();
+        let strings: Vec<_> = table.iter().collect::<Result<_, _>>().unwrap();
+
+        assert_eq!(table.get(world).unwrap(), "world");
+        assert_eq!(strings, [(hello, "hello"), (world, "world")]);
+    }
+}
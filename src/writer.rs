@@ -0,0 +1,225 @@
+use std::marker::PhantomData;
+
+use crate::pod::PaddingFree;
+use crate::{align_up, Mbuf, MbufError, MbufLength, Pod, Region, RegionHeader};
+
+/// Appends Mbufs with lengths stored as `L` back to back into a [`Region`].
+/// <br>Offsets are aligned relative to the start of the region, so its first byte
+/// must be aligned to the largest [`Mbuf::required_align`] of the records written,
+/// e.g. by using an [`crate::AlignedBuffer`].
+pub struct MbufWriter<R, L = usize> {
+    region: R,
+    position: usize,
+    _marker: PhantomData<L>,
+}
+
+impl<R: Region> MbufWriter<R> {
+    /// Creates a writer which starts at the beginning of `region`.
+    pub fn new(region: R) -> Self {
+        Self::with_position(region, 0)
+    }
+
+    /// Creates a writer which continues at byte `position` of `region`.
+    pub fn with_position(region: R, position: usize) -> Self {
+        Self {
+            region,
+            position,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Creates a writer which starts with a [`RegionHeader`] describing Mbuf<M, D> records.
+    pub fn with_header<M, D>(region: R) -> Result<Self, MbufError> {
+        let mut writer = Self::new(region);
+
+        writer.write_header::<M, D>()?;
+
+        Ok(writer)
+    }
+}
+
+impl<R: Region, L: MbufLength> MbufWriter<R, L> {
+    /// Converts this writer into one which stores lengths as `T`.
+    pub fn with_length_type<T: MbufLength>(self) -> MbufWriter<R, T> {
+        MbufWriter {
+            region: self.region,
+            position: self.position,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Writes a [`RegionHeader`] describing Mbuf<M, D, L> records to the beginning of the region.
+    /// <br>Returns an error without writing anything if records were appended already.
+    pub fn write_header<M, D>(&mut self) -> Result<(), MbufError> {
+        if self.position != 0 {
+            return Err(MbufError::InvalidMetadata("region was written to already"));
+        }
+
+        let size = std::mem::size_of::<RegionHeader>();
+
+        if self.region.bytes().len() < size {
+            self.region.grow(size)?;
+        }
+
+        RegionHeader::new::<M, D, L>().write(self.region.bytes_mut())?;
+        self.position = size;
+
+        Ok(())
+    }
+
+    /// Number of bytes written so far, including padding.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    pub fn region(&self) -> &R {
+        &self.region
+    }
+
+    pub fn into_inner(self) -> R {
+        self.region
+    }
+
+    pub(crate) fn bytes_mut(&mut self) -> &mut [u8] {
+        self.region.bytes_mut()
+    }
+
+    /// Appends an Mbuf holding `metadata` and `data`, returning its offset within the region.
+    pub fn append<M: Pod, D: Pod>(&mut self, metadata: M, data: &[D]) -> Result<usize, MbufError> {
+        let offset = self.reserve::<M, D>(data.len())?;
+
+        Mbuf::<M, D, L>::write_to_bytes_at(self.region.bytes_mut(), offset, metadata, data)?;
+
+        Ok(offset)
+    }
+
+    /// [`MbufWriter::append`] for elements which are plain data, but do not implement [`Pod`].
+    pub(crate) fn append_padding_free<M: Pod, D: PaddingFree>(
+        &mut self,
+        metadata: M,
+        data: &[D],
+    ) -> Result<usize, MbufError> {
+        let () = D::NO_PADDING;
+        let offset = self.reserve::<M, D>(data.len())?;
+
+        // reserve checked alignment and bounds, and `D` has no padding,
+        // so no uninitialized bytes end up in the region
+        unsafe {
+            Mbuf::<M, D, L>::write_to_ptr(
+                self.region.bytes_mut().as_mut_ptr().add(offset),
+                metadata,
+                data,
+            );
+        }
+
+        Ok(offset)
+    }
+
+    /// Reserves zeroed, aligned space for an Mbuf<M, D, L> of `length` elements.
+    /// <br>Returns the offset of the reserved space and advances past it.
+    pub fn reserve<M, D>(&mut self, length: usize) -> Result<usize, MbufError> {
+        if L::from_usize(length).is_none() {
+            return Err(MbufError::LengthOverflow { length });
+        }
+
+        self.reserve_bytes(
+            Mbuf::<M, D, L>::total_size(length)?,
+            Mbuf::<M, D, L>::required_align(),
+        )
+    }
+
+    /// Reserves `size` zeroed bytes at the next offset aligned to `alignment`.
+    pub(crate) fn reserve_bytes(
+        &mut self,
+        size: usize,
+        alignment: usize,
+    ) -> Result<usize, MbufError> {
+        let offset = align_up(self.position, alignment);
+        let end = size
+            .checked_add(offset)
+            .ok_or(MbufError::LengthOverflow { length: size })?;
+
+        if end > self.region.bytes().len() {
+            self.region.grow(end)?;
+        }
+
+        let bytes = self.region.bytes_mut();
+        let address = bytes.as_ptr() as usize + offset;
+
+        if !address.is_multiple_of(alignment) {
+            return Err(MbufError::MisalignedPointer {
+                address,
+                align: alignment,
+            });
+        }
+
+        bytes[self.position..end].fill(0);
+        self.position = end;
+
+        Ok(offset)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use crate::AlignedBuffer;
+
+    /// Appends a record of 3 bytes, then the one written by `append`, which thus does not start at offset 0.
+    pub(crate) fn write_after_record(
+        append: impl FnOnce(&mut MbufWriter<AlignedBuffer>) -> Result<usize, MbufError>,
+    ) -> (AlignedBuffer, usize) {
+        let mut writer = MbufWriter::new(AlignedBuffer::new());
+        writer.append(0u8, &[0u8; 3]).unwrap();
+        let offset = append(&mut writer).unwrap();
+
+        (writer.into_inner(), offset)
+    }
+
+    #[test]
+    fn aligns_records() {
+        let mut writer = MbufWriter::new(AlignedBuffer::new());
+        let a = writer.append(1u32, &[1u8, 2, 3]).unwrap();
+        let b = writer.append(2u64, &[7u16; 5]).unwrap();
+        let c = writer.append(3u8, &[9u128]).unwrap();
+
+        assert_eq!(a, 0);
+        assert_eq!(b, 24);
+        assert!(c.is_multiple_of(Mbuf::<u8, u128>::required_align()));
+        assert_eq!(writer.position(), writer.region().len());
+
+        let bytes = writer.into_inner();
+        assert_eq!(
+            &**Mbuf::<u64, u16>::from_bytes_at(&bytes, b).unwrap(),
+            &[7; 5]
+        );
+        assert_eq!(&**Mbuf::<u8, u128>::from_bytes_at(&bytes, c).unwrap(), &[9]);
+    }
+
+    #[test]
+    fn header_only_before_records() {
+        let mut writer = MbufWriter::new(AlignedBuffer::new());
+        writer.write_header::<u32, u16>().unwrap();
+        let offset = writer.append(1u32, &[2u16]).unwrap();
+        let before = writer.region().clone();
+
+        assert!(matches!(
+            writer.write_header::<u32, u16>(),
+            Err(MbufError::InvalidMetadata(_))
+        ));
+        assert_eq!(writer.region().as_slice(), before.as_slice());
+        assert_eq!(offset, std::mem::size_of::<RegionHeader>());
+    }
+
+    #[test]
+    fn fixed_region_too_small() {
+        let mut buffer = AlignedBuffer::zeroed(32);
+        let mut writer = MbufWriter::new(&mut buffer[..]);
+
+        writer.append(1u32, &[1u8, 2, 3]).unwrap();
+        assert!(matches!(
+            writer.append(1u32, &[1u8, 2, 3]),
+            Err(MbufError::RegionTooSmall { .. })
+        ));
+    }
+}